@@ -0,0 +1,266 @@
+use crate::tsp_lib::node::TSPNodeID;
+
+use super::bb_flipper::BBFlip;
+use super::flipper::Flipper;
+
+/// A flipper that stores the tour in an array together with the position of
+/// each node in that array. A flip reverses the shorter one of the two paths
+/// between the flipped nodes, while next, prev and sequence are plain array
+/// accesses. This keeps the overhead small, which pays off for small instances
+pub struct
+BBArrayFlipper
+{
+	tour:                              Vec<TSPNodeID>,
+	position:                          Vec<usize>,
+	reversed:                          bool,
+	flips:                             Vec<BBFlip>,
+	pub total_flips:                   usize,
+	pub total_unflips:                 usize,
+}
+
+impl
+BBArrayFlipper
+{
+	/// The next node in the internal array, i.e. ignoring the reversed flag
+	fn
+	internal_next
+	(
+		&self,
+		tsp_node_id:                   &TSPNodeID,
+	)
+	-> TSPNodeID
+	{
+		return self.tour[(self.position[*tsp_node_id] + 1) % self.tour.len()];
+	}
+
+	/// The previous node in the internal array, i.e. ignoring the reversed flag
+	fn
+	internal_prev
+	(
+		&self,
+		tsp_node_id:                   &TSPNodeID,
+	)
+	-> TSPNodeID
+	{
+		return self.tour[(self.position[*tsp_node_id] + self.tour.len() - 1) % self.tour.len()];
+	}
+
+	/// Checks if the middle node lies on the internal path that starts at the
+	/// start node and ends at the end node (both included)
+	fn
+	internal_between
+	(
+		&self,
+		start:                         &TSPNodeID,
+		middle:                        &TSPNodeID,
+		end:                           &TSPNodeID,
+	)
+	-> bool
+	{
+		let n = self.tour.len();
+		let start_position = self.position[*start];
+		let middle_distance = (self.position[*middle] + n - start_position) % n;
+		let end_distance = (self.position[*end] + n - start_position) % n;
+
+		return middle_distance <= end_distance;
+	}
+
+	/// The internal function for performing a flip, which reverses the path
+	/// from x to y. This can only be called via the publicly available flip
+	/// and unflip methods
+	fn
+	internal_flip
+	(
+		&mut self,
+		x:                             &TSPNodeID,
+		y:                             &TSPNodeID,
+	)
+	{
+		assert!(x != y);
+
+		// Special case: If y is the predecessor of x, BBFlipper swaps the two
+		// nodes instead of reversing the whole tour except for the edge (y, x)
+		let (start, end) = if (&self.next(y) == x) && (&self.next(x) != y) { (y, x) } else { (x, y) };
+
+		// The path in the internal array goes the other way if the tour as a
+		// whole is reversed
+		if self.reversed
+		{
+			self.internal_reverse(*end, *start);
+		}
+		else
+		{
+			self.internal_reverse(*start, *end);
+		}
+	}
+
+	/// Reverses the path from start to end in the internal array
+	/// Reversing the rest of the tour and the tour as a whole instead yields
+	/// the same result, which is done if that rest is shorter
+	fn
+	internal_reverse
+	(
+		&mut self,
+		start:                         TSPNodeID,
+		end:                           TSPNodeID,
+	)
+	{
+		let n = self.tour.len();
+		let path_length = (self.position[end] + n - self.position[start]) % n + 1;
+
+		let (start, end, path_length) = if 2 * path_length > n
+		{
+			self.reversed = !self.reversed;
+			(self.internal_next(&end), self.internal_prev(&start), n - path_length)
+		}
+		else
+		{
+			(start, end, path_length)
+		};
+
+		// Swap the nodes from both ends of the path towards its middle
+		let mut i = self.position[start];
+		let mut j = self.position[end];
+		for _ in 0..path_length/2
+		{
+			self.tour.swap(i, j);
+			self.position[self.tour[i]] = i;
+			self.position[self.tour[j]] = j;
+
+			i = (i + 1) % n;
+			j = (j + n - 1) % n;
+		}
+	}
+}
+
+impl
+Flipper
+for
+BBArrayFlipper
+{
+	/// Create a new flipper for a given tour that is represented in cycle form
+	/// using a vector of TSPNodeIDs
+	fn
+	new
+	(
+		tour:                          &[TSPNodeID]
+	)
+	-> Self
+	{
+		let mut position = vec![0; *tour.iter().max().unwrap() + 1];
+		for (index, node_id) in tour.iter().enumerate()
+		{
+			position[*node_id] = index;
+		}
+
+		BBArrayFlipper
+		{
+			tour:                      tour.to_vec(),
+			position:                  position,
+			reversed:                  false,
+			flips:                     Vec::new(),
+			total_flips:               0,
+			total_unflips:             0,
+		}
+	}
+
+	/// Get the TSPNodeID of the node subsequent node in the current tour
+	fn
+	next
+	(
+		&self,
+		tsp_node_id:                   &TSPNodeID
+	)
+	-> TSPNodeID
+	{
+		if self.reversed { self.internal_prev(tsp_node_id) } else { self.internal_next(tsp_node_id) }
+	}
+
+	/// Get the TSPNodeID of the node preceeding node in the current tour
+	fn
+	prev
+	(
+		&self,
+		tsp_node_id:                   &TSPNodeID
+	)
+	-> TSPNodeID
+	{
+		if self.reversed { self.internal_next(tsp_node_id) } else { self.internal_prev(tsp_node_id) }
+	}
+
+	/// Check if three nodes form a sequence in the current tour
+	fn
+	sequence
+	(
+		&self,
+		start:                         &TSPNodeID,
+		middle:                        &TSPNodeID,
+		end:                           &TSPNodeID,
+	)
+	-> bool
+	{
+		// Just like for BBFlipper, the start node itself is not considered to
+		// be part of the sequence, unless it is also the end node
+		if middle == start && start == end { panic!("This should not happen when calling sequence"); }
+		if middle == end   { return true; }
+		if middle == start { return false; }
+		if start == end    { return true; }
+
+		if self.reversed
+		{
+			return self.internal_between(end, middle, start);
+		}
+		return self.internal_between(start, middle, end);
+	}
+
+	/// Perform a flip on the tour and keep track of it on the internal flip stack
+	fn
+	flip
+	(
+		&mut self,
+		x:                             TSPNodeID,
+		y:                             TSPNodeID,
+	)
+	{
+		self.flips.push(BBFlip { x: x, y: y });
+		self.internal_flip(&x, &y);
+		self.total_flips += 1;
+	}
+
+	/// Undo a flip. Checks that this undos the most recent flip performed by
+	/// comparing with the top of the flip stack
+	fn
+	unflip
+	(
+		&mut self,
+		x:                             TSPNodeID,
+		y:                             TSPNodeID,
+	)
+	{
+		assert!(!self.flips.is_empty());
+		assert_eq!(self.flips.last().unwrap().x, x);
+		assert_eq!(self.flips.last().unwrap().y, y);
+
+		self.flips.pop();
+
+		self.internal_flip(&y, &x);
+		self.total_unflips += 1;
+	}
+
+	/// Converts the tour currently stored by the flipper into a cycle that
+	/// is represented via a vector
+	fn
+	as_cycle
+	(
+		&self
+	)
+	-> Vec<TSPNodeID>
+	{
+		let n = self.tour.len();
+		let start_position = self.position[0];
+
+		return (0..n)
+			.map(|i| if self.reversed { self.tour[(start_position + n - i) % n] } else { self.tour[(start_position + i) % n] })
+			.collect();
+	}
+}
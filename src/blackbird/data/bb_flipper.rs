@@ -3,16 +3,16 @@ use std::collections::HashSet;
 use std::rc::Weak;
 use std::rc::Rc;
 
-use crate::tsp_lib::data::TSPData;
 use crate::tsp_lib::node::TSPNodeID;
-use crate::tsp_lib::node::TSPWeight;
+
+use super::flipper::Flipper;
 
 /// Struct for storing a flip on the internal flip stack of the BBFlipper
 pub struct
 BBFlip
 {
-	x:                                 TSPNodeID,
-	y:                                 TSPNodeID,
+	pub x:                             TSPNodeID,
+	pub y:                             TSPNodeID,
 }
 
 /// The main flipper structure that supports flip, next, prev, sequence, ...
@@ -46,7 +46,7 @@ BBFlipper
 	pub fn
 	new
 	(
-		tour:                          &Vec<TSPNodeID>
+		tour:                          &[TSPNodeID]
 	)
 	-> Self
 	{
@@ -185,7 +185,7 @@ BBFlipper
 		y:                             TSPNodeID,	
 	)
 	{
-		assert!(self.flips.len() > 0);
+		assert!(!self.flips.is_empty());
 		assert_eq!(self.flips.last().unwrap().x, x);
 		assert_eq!(self.flips.last().unwrap().y, y);
 
@@ -300,9 +300,14 @@ BBFlipper
 		let mut cycle = Vec::with_capacity(self.children.len());
 		cycle.push(start_node);
 
+		// Remember which nodes have been visited to detect a broken cycle
+		let mut visited = vec![false; self.children.len()];
+		visited[start_node] = true;
+
 		while current_node != start_node
 		{
-			assert!(!cycle.contains(&current_node));
+			assert!(!visited[current_node]);
+			visited[current_node] = true;
 			cycle.push(current_node);
 			current_node = self.next(&current_node);
 		}
@@ -311,24 +316,88 @@ BBFlipper
 
 		return cycle;
 	}
+}
 
-	/// Computes the cost of the tour currently stored by the flipper
-	pub fn
-	cost
+impl
+Flipper
+for
+BBFlipper
+{
+	fn
+	new
+	(
+		tour:                          &[TSPNodeID]
+	)
+	-> Self
+	{
+		BBFlipper::new(tour)
+	}
+
+	fn
+	next
 	(
 		&self,
-		tsp_data:                      &TSPData,
+		tsp_node_id:                   &TSPNodeID
 	)
-	-> TSPWeight
+	-> TSPNodeID
 	{
-		let cycle = self.as_cycle();
-		let mut length = 0.0;
-		for i in 0..cycle.len()
-		{
-			length += tsp_data.get_distance_between_via_id(cycle[i], cycle[(i+1)%tsp_data.n]);
-		}
+		BBFlipper::next(self, tsp_node_id)
+	}
 
-		return length;
+	fn
+	prev
+	(
+		&self,
+		tsp_node_id:                   &TSPNodeID
+	)
+	-> TSPNodeID
+	{
+		BBFlipper::prev(self, tsp_node_id)
+	}
+
+	fn
+	sequence
+	(
+		&self,
+		start:                         &TSPNodeID,
+		middle:                        &TSPNodeID,
+		end:                           &TSPNodeID,
+	)
+	-> bool
+	{
+		BBFlipper::sequence(self, start, middle, end)
+	}
+
+	fn
+	flip
+	(
+		&mut self,
+		x:                             TSPNodeID,
+		y:                             TSPNodeID,
+	)
+	{
+		BBFlipper::flip(self, x, y)
+	}
+
+	fn
+	unflip
+	(
+		&mut self,
+		x:                             TSPNodeID,
+		y:                             TSPNodeID,
+	)
+	{
+		BBFlipper::unflip(self, x, y)
+	}
+
+	fn
+	as_cycle
+	(
+		&self
+	)
+	-> Vec<TSPNodeID>
+	{
+		BBFlipper::as_cycle(self)
 	}
 }
 
@@ -0,0 +1,170 @@
+use std::collections::HashMap;
+use std::collections::VecDeque;
+
+use rand::rngs::StdRng;
+
+use crate::tsp_lib::node::*;
+
+use crate::blackbird::data::bb_data::*;
+use crate::blackbird::data::bb_edge::*;
+
+use super::flipper::Flipper;
+
+/// The BBSearch struct holds the state of a single trial of CLK, i.e. all the
+/// data that gets modified while applying the heuristic. The instance and
+/// everything computed from it beforehand is only borrowed from the BBData
+/// struct, so that multiple trials can work on the same BBData at once
+pub struct
+BBSearch<'a, F: Flipper>
+{
+	pub data:                          &'a BBData<'a>,
+	pub current_tour_length:           TSPWeight,
+
+	pub node_queue:                    VecDeque<TSPNodeID>,
+	pub edge_markings:                 HashMap<BBEdge, EEdgeMarking>,
+
+	pub flipper:                       Option<F>,
+	pub output_tour_cycle:             Option<Vec<TSPNodeID>>,
+	pub output_tour_length:            TSPWeight,
+
+	// Statistics about the kicks performed by this trial of CLK
+	pub kick_count:                    usize,
+	pub improving_kick_count:          usize,
+	pub dropped_kick_count:            usize,
+
+	// Needed for the alternate_step 
+	pub weirdmark:                     HashMap<TSPNodeID, i64>,
+	pub weirdmagic:                    i64,
+
+	pub random_generator:              StdRng,
+}
+
+impl<'a, F: Flipper>
+BBSearch<'a, F>
+{
+	/// Creates the BBSearch struct for a trial of CLK on the given data,
+	/// starting from its initial tour. The random generator of the trial is
+	/// based on the given seed
+	pub fn
+	new
+	(
+		data:     &'a BBData<'a>,
+		seed:     u64,
+	)
+	-> BBSearch<'a, F>
+	{
+		BBSearch
+		{
+			data:                      data,
+			current_tour_length:       data.initial_tour_length,
+
+			node_queue:                VecDeque::new(),
+			edge_markings:             HashMap::new(),
+
+			flipper:                   None,
+			output_tour_cycle:         data.initial_tour_cycle.clone(),
+			output_tour_length:        data.initial_tour_length,
+
+			kick_count:                0,
+			improving_kick_count:      0,
+			dropped_kick_count:        0,
+
+			weirdmark:                 HashMap::new(),
+			weirdmagic:                0,
+
+			random_generator:          rand_seeder::Seeder::from(seed).make_rng(),
+		}
+	}
+
+	/// Checks if the time limit has been reached, which is measured from the
+	/// start of solving the instance
+	pub fn
+	time_limit_reached
+	(
+		&self
+	)
+	-> bool
+	{
+		return self.data.time_limit_reached();
+	}
+
+	/// Marks an edge as being deleted during tour improvement 
+	/// These markings are required for the creations of the orderings used in
+	/// the step methods
+	pub fn
+	mark_edge_as_deleted
+	(
+		&mut self,
+		edge:                          &BBEdge
+	)
+	{
+		self.edge_markings.insert(*edge, EEdgeMarking::DELETED);
+	}
+
+	/// Removes the deleted marking from an edge
+	pub fn
+	unmark_edge_as_deleted
+	(
+		&mut self,
+		edge:                          &BBEdge
+	)
+	{
+		self.edge_markings.insert(*edge, EEdgeMarking::NONE);
+	}
+
+	/// Checks if a given edge has the deleted marking
+	pub fn
+	is_edge_deleted
+	(
+		&self,
+		edge:                          &BBEdge
+	)
+	-> bool
+	{
+		if let Some(marking) = self.edge_markings.get(edge)
+		{
+			return marking.eq(&EEdgeMarking::DELETED);
+		}
+		return false;
+	}
+
+	/// Marks an edge as being added during tour improvement 
+	/// These markings are required for the creations of the orderings used in
+	/// the step methods
+	pub fn
+	mark_edge_as_added
+	(
+		&mut self,
+		edge:                          &BBEdge
+	)
+	{
+		self.edge_markings.insert(*edge, EEdgeMarking::ADDED);
+	}
+
+	/// Removes the added marking from an edge
+	pub fn
+	unmark_edge_as_added
+	(
+		&mut self,
+		edge:                          &BBEdge
+	)
+	{
+		self.edge_markings.insert(*edge, EEdgeMarking::NONE);
+	}
+
+	/// Checks if a given edge has the added marking
+	pub fn
+	is_edge_added
+	(
+		&self,
+		edge:                          &BBEdge
+	)
+	-> bool
+	{
+		if let Some(marking) = self.edge_markings.get(edge)
+		{
+			return marking.eq(&EEdgeMarking::ADDED);
+		}
+		return false;
+	}
+}
\ No newline at end of file
@@ -0,0 +1,176 @@
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+
+use crate::blackbird::data::bb_data::BBData;
+use crate::tsp_lib::node::TSPNodeID;
+use crate::tsp_lib::node::TSPWeight;
+
+/// Marks the root of the spanning tree, which has no parent
+pub const NO_PARENT: usize = usize::MAX;
+
+/// An entry of the priority queue used by Prim's algorithm, ordered such
+/// that the cheapest entry is on top of the (max-)heap
+struct
+BBPrimEntry
+{
+	cost:                              TSPWeight,
+	node:                              TSPNodeID,
+	parent:                            TSPNodeID,
+}
+
+impl
+PartialEq
+for
+BBPrimEntry
+{
+	fn
+	eq
+	(
+		&self,
+		other: &Self
+	)
+	-> bool
+	{
+		self.cost == other.cost
+	}
+}
+
+impl Eq for BBPrimEntry {}
+
+impl
+PartialOrd
+for
+BBPrimEntry
+{
+	fn
+	partial_cmp
+	(
+		&self,
+		other: &Self
+	)
+	-> Option<Ordering>
+	{
+		Some(self.cmp(other))
+	}
+}
+
+impl
+Ord
+for
+BBPrimEntry
+{
+	fn
+	cmp
+	(
+		&self,
+		other: &Self
+	)
+	-> Ordering
+	{
+		other.cost.total_cmp(&self.cost)
+	}
+}
+
+/// A minimum spanning tree, given by the parent of each node and an order of
+/// the nodes in which each parent comes before its children
+pub struct
+BBSpanningTree
+{
+	pub parent:                        Vec<TSPNodeID>,
+	pub order:                         Vec<TSPNodeID>,
+	pub length:                        TSPWeight,
+}
+
+impl
+BBData<'_>
+{
+	/// Computes a minimum spanning tree for the edge costs modified by the
+	/// given penalties using Prim's algorithm. Uses only the given adjacency
+	/// lists if there are any and all edges otherwise. Returns None if the
+	/// nodes can not all be connected by the given edges
+	pub fn
+	minimum_spanning_tree
+	(
+		&self,
+		penalties:                     &[TSPWeight],
+		adjacency:                     &Option<Vec<Vec<TSPNodeID>>>,
+	)
+	-> Option<BBSpanningTree>
+	{
+		let n = self.tsp_data.n;
+		let cost = |a: TSPNodeID, b: TSPNodeID| self.tsp_data.get_distance_between_via_id(a, b) + penalties[a] + penalties[b];
+
+		let mut parent = vec![NO_PARENT; n];
+		let mut in_tree = vec![false; n];
+		let mut order = Vec::with_capacity(n);
+		let mut length = 0.0;
+
+		match adjacency
+		{
+			Some(adjacency) =>
+			{
+				let mut heap = BinaryHeap::new();
+				heap.push(BBPrimEntry { cost: 0.0, node: 0, parent: NO_PARENT });
+
+				while let Some(entry) = heap.pop()
+				{
+					if in_tree[entry.node]
+					{
+						continue;
+					}
+
+					in_tree[entry.node] = true;
+					parent[entry.node] = entry.parent;
+					order.push(entry.node);
+					length += entry.cost;
+
+					for &other_node_id in &adjacency[entry.node]
+					{
+						if !in_tree[other_node_id]
+						{
+							heap.push(BBPrimEntry { cost: cost(entry.node, other_node_id), node: other_node_id, parent: entry.node });
+						}
+					}
+				}
+
+				if order.len() < n
+				{
+					return None;
+				}
+			}
+			None =>
+			{
+				// Dense version, which keeps the cheapest connection of each
+				// node to the tree and scans all of them in each step
+				let mut connection = vec![(TSPWeight::MAX, NO_PARENT); n];
+				connection[0] = (0.0, NO_PARENT);
+
+				for _ in 0..n
+				{
+					let next = (0..n).filter(|node_id| !in_tree[*node_id])
+						.min_by(|a, b| connection[*a].0.total_cmp(&connection[*b].0))
+						.unwrap();
+
+					in_tree[next] = true;
+					parent[next] = connection[next].1;
+					order.push(next);
+					length += connection[next].0;
+
+					for other_node_id in 0..n
+					{
+						if !in_tree[other_node_id]
+						{
+							let edge_cost = cost(next, other_node_id);
+							if edge_cost < connection[other_node_id].0
+							{
+								connection[other_node_id] = (edge_cost, next);
+							}
+						}
+					}
+				}
+			}
+		}
+
+		return Some(BBSpanningTree { parent: parent, order: order, length: length });
+	}
+}
@@ -0,0 +1,544 @@
+use std::cell::RefCell;
+
+use crate::tsp_lib::node::TSPNodeID;
+
+use super::bb_flipper::BBFlip;
+use super::flipper::Flipper;
+
+/// Marks a missing child or parent in the splay tree
+const NIL: usize = usize::MAX;
+
+/// A flipper that stores the tour as splay tree, similar to flip_sp2.c of
+/// Concorde. The in-order traversal of the tree yields the tour, and every
+/// subtree can be reversed via a (lazily propagated) flag, so that all of the
+/// operations take O(log(n)) amortized time.
+/// As even read-only operations like next restructure the tree, the tree is
+/// stored in a RefCell
+pub struct
+BBSplayFlipper
+{
+	tree:                              RefCell<BBSplayTree>,
+	reversed:                          bool,
+	flips:                             Vec<BBFlip>,
+	pub total_flips:                   usize,
+	pub total_unflips:                 usize,
+}
+
+/// The splay tree itself, with the nodes of the tree being the TSP nodes
+/// The size of a node is the number of nodes in its subtree
+struct
+BBSplayTree
+{
+	root:                              usize,
+	left:                              Vec<usize>,
+	right:                             Vec<usize>,
+	parent:                            Vec<usize>,
+	size:                              Vec<usize>,
+	reversed:                          Vec<bool>,
+}
+
+impl
+BBSplayTree
+{
+	/// Creates a balanced tree whose in-order traversal is the given tour
+	fn
+	new
+	(
+		tour:                          &[TSPNodeID]
+	)
+	-> Self
+	{
+		let node_count = *tour.iter().max().unwrap() + 1;
+		let mut tree = BBSplayTree
+		{
+			root:                      NIL,
+			left:                      vec![NIL; node_count],
+			right:                     vec![NIL; node_count],
+			parent:                    vec![NIL; node_count],
+			size:                      vec![1; node_count],
+			reversed:                  vec![false; node_count],
+		};
+
+		tree.root = tree.build(tour, NIL);
+
+		return tree;
+	}
+
+	/// Recursively builds a balanced (sub-)tree for the given part of the tour
+	fn
+	build
+	(
+		&mut self,
+		tour:                          &[TSPNodeID],
+		parent:                        usize,
+	)
+	-> usize
+	{
+		if tour.is_empty()
+		{
+			return NIL;
+		}
+
+		let middle = tour.len() / 2;
+		let node = tour[middle];
+
+		self.parent[node] = parent;
+		self.left[node]   = self.build(&tour[..middle], node);
+		self.right[node]  = self.build(&tour[middle+1..], node);
+		self.size[node]   = tour.len();
+
+		return node;
+	}
+
+	/// The size of a subtree, which is 0 for a missing one
+	fn
+	size_of
+	(
+		&self,
+		node:                          usize,
+	)
+	-> usize
+	{
+		if node == NIL { 0 } else { self.size[node] }
+	}
+
+	/// Recomputes the size of a node from its children
+	fn
+	update
+	(
+		&mut self,
+		node:                          usize,
+	)
+	{
+		self.size[node] = 1 + self.size_of(self.left[node]) + self.size_of(self.right[node]);
+	}
+
+	/// Pushes the reversed flag of a node down to its children
+	fn
+	push
+	(
+		&mut self,
+		node:                          usize,
+	)
+	{
+		if self.reversed[node]
+		{
+			let (left, right) = (self.left[node], self.right[node]);
+			self.left[node] = right;
+			self.right[node] = left;
+			if left  != NIL { self.reversed[left]  = !self.reversed[left];  }
+			if right != NIL { self.reversed[right] = !self.reversed[right]; }
+			self.reversed[node] = false;
+		}
+	}
+
+	/// Rotates a node above its parent
+	fn
+	rotate
+	(
+		&mut self,
+		node:                          usize,
+	)
+	{
+		let parent = self.parent[node];
+		let grandparent = self.parent[parent];
+
+		if self.left[parent] == node
+		{
+			let child = self.right[node];
+			self.left[parent] = child;
+			if child != NIL { self.parent[child] = parent; }
+			self.right[node] = parent;
+		}
+		else
+		{
+			let child = self.left[node];
+			self.right[parent] = child;
+			if child != NIL { self.parent[child] = parent; }
+			self.left[node] = parent;
+		}
+
+		self.parent[parent] = node;
+		self.parent[node] = grandparent;
+
+		if grandparent != NIL
+		{
+			if self.left[grandparent] == parent { self.left[grandparent] = node; } else { self.right[grandparent] = node; }
+		}
+
+		self.update(parent);
+		self.update(node);
+	}
+
+	/// Moves a node up until its parent is the given one (or until it is the
+	/// root of the tree, if the given parent is NIL)
+	fn
+	splay
+	(
+		&mut self,
+		node:                          usize,
+		target_parent:                 usize,
+	)
+	{
+		// The reversed flags above the node need to be pushed down first, as
+		// the rotations change the structure above the node
+		let mut ancestors = Vec::new();
+		let mut current = node;
+		while current != NIL
+		{
+			ancestors.push(current);
+			current = self.parent[current];
+		}
+		for ancestor in ancestors.iter().rev()
+		{
+			self.push(*ancestor);
+		}
+
+		while self.parent[node] != target_parent
+		{
+			let parent = self.parent[node];
+			let grandparent = self.parent[parent];
+
+			if grandparent != target_parent
+			{
+				let zig_zig = (self.left[grandparent] == parent) == (self.left[parent] == node);
+				if zig_zig { self.rotate(parent); } else { self.rotate(node); }
+			}
+			self.rotate(node);
+		}
+
+		if target_parent == NIL
+		{
+			self.root = node;
+		}
+	}
+
+	/// The position of a node in the in-order traversal
+	fn
+	rank
+	(
+		&mut self,
+		node:                          usize,
+	)
+	-> usize
+	{
+		self.splay(node, NIL);
+		return self.size_of(self.left[node]);
+	}
+
+	/// The node at the given position of the in-order traversal
+	fn
+	at
+	(
+		&mut self,
+		rank:                          usize,
+	)
+	-> usize
+	{
+		let mut node = self.root;
+		let mut rank = rank;
+		loop
+		{
+			self.push(node);
+			let left_size = self.size_of(self.left[node]);
+			if rank < left_size
+			{
+				node = self.left[node];
+			}
+			else if rank == left_size
+			{
+				break;
+			}
+			else
+			{
+				rank -= left_size + 1;
+				node = self.right[node];
+			}
+		}
+
+		self.splay(node, NIL);
+		return node;
+	}
+
+	/// Reverses the part of the in-order traversal from the first to the last
+	/// given position (both included)
+	fn
+	reverse
+	(
+		&mut self,
+		first_rank:                    usize,
+		last_rank:                     usize,
+	)
+	{
+		let node_count = self.size_of(self.root);
+
+		// Gather the part in a single subtree, which is the whole tree, the
+		// right subtree of the node before it, the left subtree of the node
+		// after it or the left subtree of the right child of the node before it
+		let subtree = if first_rank == 0 && last_rank + 1 == node_count
+		{
+			self.root
+		}
+		else if last_rank + 1 == node_count
+		{
+			let before = self.at(first_rank - 1);
+			self.right[before]
+		}
+		else if first_rank == 0
+		{
+			let after = self.at(last_rank + 1);
+			self.left[after]
+		}
+		else
+		{
+			let before = self.at(first_rank - 1);
+			let after = self.at(last_rank + 1);
+			self.splay(before, after);
+			self.right[before]
+		};
+
+		self.reversed[subtree] = !self.reversed[subtree];
+	}
+}
+
+impl
+BBSplayFlipper
+{
+	/// The next node in the in-order traversal, i.e. ignoring the reversed
+	/// flag of the whole tour
+	fn
+	internal_next
+	(
+		&self,
+		tsp_node_id:                   &TSPNodeID,
+	)
+	-> TSPNodeID
+	{
+		let mut tree = self.tree.borrow_mut();
+		let node_count = tree.size_of(tree.root);
+		let rank = tree.rank(*tsp_node_id);
+		return tree.at((rank + 1) % node_count);
+	}
+
+	/// The previous node in the in-order traversal, i.e. ignoring the reversed
+	/// flag of the whole tour
+	fn
+	internal_prev
+	(
+		&self,
+		tsp_node_id:                   &TSPNodeID,
+	)
+	-> TSPNodeID
+	{
+		let mut tree = self.tree.borrow_mut();
+		let node_count = tree.size_of(tree.root);
+		let rank = tree.rank(*tsp_node_id);
+		return tree.at((rank + node_count - 1) % node_count);
+	}
+
+	/// Checks if the middle node lies on the internal path that starts at the
+	/// start node and ends at the end node (both included)
+	fn
+	internal_between
+	(
+		&self,
+		start:                         &TSPNodeID,
+		middle:                        &TSPNodeID,
+		end:                           &TSPNodeID,
+	)
+	-> bool
+	{
+		let mut tree = self.tree.borrow_mut();
+		let n = tree.size_of(tree.root);
+		let start_rank = tree.rank(*start);
+		let middle_distance = (tree.rank(*middle) + n - start_rank) % n;
+		let end_distance = (tree.rank(*end) + n - start_rank) % n;
+
+		return middle_distance <= end_distance;
+	}
+
+	/// The internal function for performing a flip, which reverses the path
+	/// from x to y. This can only be called via the publicly available flip
+	/// and unflip methods
+	fn
+	internal_flip
+	(
+		&mut self,
+		x:                             &TSPNodeID,
+		y:                             &TSPNodeID,
+	)
+	{
+		assert!(x != y);
+
+		// Special case: If y is the predecessor of x, BBFlipper swaps the two
+		// nodes instead of reversing the whole tour except for the edge (y, x)
+		let (start, end) = if (&self.next(y) == x) && (&self.next(x) != y) { (y, x) } else { (x, y) };
+
+		// The path in the in-order traversal goes the other way if the tour as
+		// a whole is reversed
+		if self.reversed
+		{
+			self.internal_reverse(*end, *start);
+		}
+		else
+		{
+			self.internal_reverse(*start, *end);
+		}
+	}
+
+	/// Reverses the path from start to end in the in-order traversal
+	/// If the path wraps around the end of the traversal, the rest of the tour
+	/// and the tour as a whole get reversed instead, which yields the same
+	/// result
+	fn
+	internal_reverse
+	(
+		&mut self,
+		start:                         TSPNodeID,
+		end:                           TSPNodeID,
+	)
+	{
+		let tree = self.tree.get_mut();
+		let start_rank = tree.rank(start);
+		let end_rank = tree.rank(end);
+
+		if start_rank <= end_rank
+		{
+			tree.reverse(start_rank, end_rank);
+		}
+		else
+		{
+			self.reversed = !self.reversed;
+			if end_rank + 1 < start_rank
+			{
+				tree.reverse(end_rank + 1, start_rank - 1);
+			}
+		}
+	}
+}
+
+impl
+Flipper
+for
+BBSplayFlipper
+{
+	/// Create a new flipper for a given tour that is represented in cycle form
+	/// using a vector of TSPNodeIDs
+	fn
+	new
+	(
+		tour:                          &[TSPNodeID]
+	)
+	-> Self
+	{
+		BBSplayFlipper
+		{
+			tree:                      RefCell::new(BBSplayTree::new(tour)),
+			reversed:                  false,
+			flips:                     Vec::new(),
+			total_flips:               0,
+			total_unflips:             0,
+		}
+	}
+
+	/// Get the TSPNodeID of the node subsequent node in the current tour
+	fn
+	next
+	(
+		&self,
+		tsp_node_id:                   &TSPNodeID
+	)
+	-> TSPNodeID
+	{
+		if self.reversed { self.internal_prev(tsp_node_id) } else { self.internal_next(tsp_node_id) }
+	}
+
+	/// Get the TSPNodeID of the node preceeding node in the current tour
+	fn
+	prev
+	(
+		&self,
+		tsp_node_id:                   &TSPNodeID
+	)
+	-> TSPNodeID
+	{
+		if self.reversed { self.internal_next(tsp_node_id) } else { self.internal_prev(tsp_node_id) }
+	}
+
+	/// Check if three nodes form a sequence in the current tour
+	fn
+	sequence
+	(
+		&self,
+		start:                         &TSPNodeID,
+		middle:                        &TSPNodeID,
+		end:                           &TSPNodeID,
+	)
+	-> bool
+	{
+		// Just like for BBFlipper, the start node itself is not considered to
+		// be part of the sequence, unless it is also the end node
+		if middle == start && start == end { panic!("This should not happen when calling sequence"); }
+		if middle == end   { return true; }
+		if middle == start { return false; }
+		if start == end    { return true; }
+
+		if self.reversed
+		{
+			return self.internal_between(end, middle, start);
+		}
+		return self.internal_between(start, middle, end);
+	}
+
+	/// Perform a flip on the tour and keep track of it on the internal flip stack
+	fn
+	flip
+	(
+		&mut self,
+		x:                             TSPNodeID,
+		y:                             TSPNodeID,
+	)
+	{
+		self.flips.push(BBFlip { x: x, y: y });
+		self.internal_flip(&x, &y);
+		self.total_flips += 1;
+	}
+
+	/// Undo a flip. Checks that this undos the most recent flip performed by
+	/// comparing with the top of the flip stack
+	fn
+	unflip
+	(
+		&mut self,
+		x:                             TSPNodeID,
+		y:                             TSPNodeID,
+	)
+	{
+		assert!(!self.flips.is_empty());
+		assert_eq!(self.flips.last().unwrap().x, x);
+		assert_eq!(self.flips.last().unwrap().y, y);
+
+		self.flips.pop();
+
+		self.internal_flip(&y, &x);
+		self.total_unflips += 1;
+	}
+
+	/// Converts the tour currently stored by the flipper into a cycle that
+	/// is represented via a vector
+	fn
+	as_cycle
+	(
+		&self
+	)
+	-> Vec<TSPNodeID>
+	{
+		let mut tree = self.tree.borrow_mut();
+		let n = tree.size_of(tree.root);
+		let start_rank = tree.rank(0);
+
+		return (0..n)
+			.map(|i| if self.reversed { tree.at((start_rank + n - i) % n) } else { tree.at((start_rank + i) % n) })
+			.collect();
+	}
+}
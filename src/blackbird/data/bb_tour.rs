@@ -1,9 +1,85 @@
+use std::fmt;
+
+use crate::tsp_lib::data::TSPData;
 use crate::tsp_lib::node::{TSPWeight, TSPNodeID};
 
 use super::bb_edge::BBEdge;
 
+/// The reasons why the edges of a BBTour do not form a valid tour
+/// The nodes are given by their internal IDs, unless the error has been
+/// converted via with_external_ids
+#[derive(Debug)]
+pub enum
+BBTourError
+{
+	UnknownNode { node: TSPNodeID },
+	MissingNodes { count: usize, example: TSPNodeID },
+	DegreeViolation { node: TSPNodeID, degree: usize },
+	Subtours { count: usize },
+}
+
+impl
+fmt::Display
+for
+BBTourError
+{
+	fn
+	fmt
+	(
+		&self,
+		f: &mut fmt::Formatter<'_>
+	)
+	-> fmt::Result
+	{
+		match self
+		{
+			BBTourError::UnknownNode { node }
+				=> write!(f, "Tour: Node {} does not exist", node),
+			BBTourError::MissingNodes { count, example }
+				=> write!(f, "Tour: {} nodes (e.g. node {}) are not visited", count, example),
+			BBTourError::DegreeViolation { node, degree }
+				=> write!(f, "Tour: Node {} has {} adjacent edges instead of 2", node, degree),
+			BBTourError::Subtours { count }
+				=> write!(f, "Tour: The edges form {} subtours instead of a single tour", count),
+		}
+	}
+}
+
+impl std::error::Error for BBTourError {}
+
+impl
+BBTourError
+{
+	/// Replaces the internal node IDs of the error by the IDs the nodes have
+	/// in the input file, so that the error can be reported to the user
+	/// Nodes that do not exist keep their ID
+	pub fn
+	with_external_ids
+	(
+		self,
+		tsp_data:                      &TSPData,
+	)
+	-> BBTourError
+	{
+		let external_id = |node_id: TSPNodeID| if node_id < tsp_data.n { tsp_data.external_id(node_id) } else { node_id };
+
+		match self
+		{
+			BBTourError::UnknownNode { node }
+				=> BBTourError::UnknownNode { node: node },
+			BBTourError::MissingNodes { count, example }
+				=> BBTourError::MissingNodes { count: count, example: external_id(example) },
+			BBTourError::DegreeViolation { node, degree }
+				=> BBTourError::DegreeViolation { node: external_id(node), degree: degree },
+			BBTourError::Subtours { count }
+				=> BBTourError::Subtours { count: count },
+		}
+	}
+}
+
 /// This data structure gets used as intermediate representation after the tour
 /// initialization. Afterwards the tour gets stored using a BBFlipper or a vector
+#[derive(Default)]
 pub struct
 BBTour
 {
@@ -48,131 +124,162 @@ BBTour
 	}
 
 	/// Checks if the set of edges stored in the struct actually form a valid
-	/// tour
+	/// tour through all n nodes. If so, the tour gets returned as cycle that
+	/// starts with node 0, otherwise the reason why it is invalid
+	/// Runs in linear time by storing the two neighbors of each node
 	pub fn
 	is_valid
 	(
 		&self,
-		do_print: bool,
+		n:                             usize,
+		do_print:                      bool,
 	)
-	-> Option<Vec<TSPNodeID>>
+	-> Result<Vec<TSPNodeID>, BBTourError>
 	{
-		// An empty tour can't be valid
-		if self.edges.is_empty()
-		{
-			return None;
-		}
-
-		// Remember which nodes have been visited
-		let mut visited_nodes = Vec::new();
+		// Store for each node its degree and (up to) two neighbors
+		let mut degree    = vec![0; n];
+		let mut neighbors = vec![[0; 2]; n];
 
-		// Start with the first edge
-		let mut current_node_id = self.edges.first().unwrap().start;
-
-		loop
+		for edge in &self.edges
 		{
-			// Add visited node
-			visited_nodes.push(current_node_id);
-			if do_print { print!(" -- {}", current_node_id); }
-
-			// Get all edges that are adjacent to the current node
-			let next_edge_candidates = self.edges.iter().filter(
-				|edge| edge.start == current_node_id || edge.end == current_node_id
-			).collect::<Vec::<&BBEdge>>();
-
-			// There need to be exactly two edges that are adjacent to this node
-			if next_edge_candidates.len() != 2
-			{
-				return None;
-			}
-
-			// Unpack the two edges
-			let edge_one = next_edge_candidates[0];
-			let edge_two = next_edge_candidates[1];
-
-			if edge_one.start == current_node_id
-			{
-				// edge_one.end is the "other" node
-				if visited_nodes.contains(&edge_one.end)
-				{
-					// edge_one has been visited - edge_two needs to be unvisited
-				}
-				else
-				{
-					current_node_id = edge_one.end;
-					continue;
-				}
-			}
-			else if edge_one.end == current_node_id
+			for (node_id, other_node_id) in [(edge.start, edge.end), (edge.end, edge.start)]
 			{
-				// edge_one.start is the "other" node
-				if visited_nodes.contains(&edge_one.start)
+				if node_id >= n
 				{
-					// edge_one has been visited - edge_two needs to be unvisited
+					return Err(BBTourError::UnknownNode { node: node_id });
 				}
-				else
+				if degree[node_id] < 2
 				{
-					current_node_id = edge_one.start;
-					continue;
+					neighbors[node_id][degree[node_id]] = other_node_id;
 				}
+				degree[node_id] += 1;
 			}
-			else
-			{
-				return None;
-			}
+		}
 
-			// edge_one has been visited - check edge_two
+		// Every node needs to be part of the tour...
+		let missing = degree.iter().filter(|node_degree| **node_degree == 0).count();
+		if missing > 0
+		{
+			return Err(BBTourError::MissingNodes { count: missing, example: degree.iter().position(|node_degree| *node_degree == 0).unwrap() });
+		}
 
-			if edge_two.start == current_node_id
+		// ...with exactly two adjacent edges
+		if let Some(node_id) = (0..n).find(|node_id| degree[*node_id] != 2)
+		{
+			return Err(BBTourError::DegreeViolation { node: node_id, degree: degree[node_id] });
+		}
+
+		// Now the edges form one or more cycles, so walk along all of them
+		// The first one starts at node 0 and is the tour if it is the only one
+		let mut visited = vec![false; n];
+		let mut cycle   = Vec::with_capacity(n);
+		let mut subtour_count = 0;
+
+		for start_node_id in 0..n
+		{
+			if visited[start_node_id]
 			{
-				// edge_two.end is the "other" node
-				if visited_nodes.contains(&edge_two.end)
-				{
-					// Both edges have been visited
-					// If at this point all nodes have been visited, i.e. their
-					// number equals the number of edges, we have a valid tour
-					if visited_nodes.len() == self.edges.len()
-					{
-						return Some(visited_nodes);
-					}
-					else
-					{
-						return None;
-					}
-				}
-				else
-				{
-					current_node_id = edge_two.end;
-					continue;
-				}
+				continue;
 			}
-			else if edge_two.end == current_node_id
+			subtour_count += 1;
+
+			let mut previous_node_id = neighbors[start_node_id][1];
+			let mut current_node_id  = start_node_id;
+			while !visited[current_node_id]
 			{
-				// edge_two.start is the "other" node
-				if visited_nodes.contains(&edge_two.start)
-				{
-					// Both edges have been visited
-					// If at this point all nodes have been visited, i.e. their
-					// number equals the number of edges, we have a valid tour
-					if visited_nodes.len() == self.edges.len()
-					{
-						return Some(visited_nodes);
-					}
-					else
-					{
-						return None;
-					}
-				}
-				else
+				visited[current_node_id] = true;
+				if subtour_count == 1
 				{
-					current_node_id = edge_two.start;
-					continue;
+					cycle.push(current_node_id);
+					if do_print { print!(" -- {}", current_node_id); }
 				}
+
+				let next_node_id = if neighbors[current_node_id][0] != previous_node_id { neighbors[current_node_id][0] } else { neighbors[current_node_id][1] };
+				previous_node_id = current_node_id;
+				current_node_id  = next_node_id;
 			}
-			else
-			{
-				return None;
-			}
 		}
+
+		if subtour_count > 1
+		{
+			return Err(BBTourError::Subtours { count: subtour_count });
+		}
+
+		return Ok(cycle);
+	}
+}
+
+#[cfg(test)]
+mod tests
+{
+	use super::*;
+
+	/// Creates a tour from the given edges, which may also be loops
+	fn
+	tour_from_edges
+	(
+		edges:                         &[(TSPNodeID, TSPNodeID)],
+	)
+	-> BBTour
+	{
+		BBTour
+		{
+			edges:                     edges.iter().map(|&(start, end)| BBEdge { start: start, end: end, weight: 0.0 }).collect(),
+		}
+	}
+
+	#[test]
+	fn
+	valid_tour_starts_at_node_0
+	()
+	{
+		let cycle = tour_from_edges(&[(2, 4), (1, 0), (3, 1), (4, 0), (2, 3)]).is_valid(5, false).unwrap();
+		assert!(cycle == vec![0, 1, 3, 2, 4] || cycle == vec![0, 4, 2, 3, 1]);
+
+		// Two nodes are connected by both edges of the tour
+		assert_eq!(tour_from_edges(&[(0, 1), (1, 0)]).is_valid(2, false).unwrap(), vec![0, 1]);
+	}
+
+	#[test]
+	fn
+	invalid_tours_report_reason
+	()
+	{
+		assert!(matches!(tour_from_edges(&[(0, 1), (1, 5), (5, 0)]).is_valid(3, false),
+			Err(BBTourError::UnknownNode { node: 5 })));
+		assert!(matches!(tour_from_edges(&[(0, 1), (1, 2), (2, 0)]).is_valid(5, false),
+			Err(BBTourError::MissingNodes { count: 2, example: 3 })));
+		assert!(matches!(tour_from_edges(&[(0, 1), (1, 2), (2, 0), (0, 3)]).is_valid(4, false),
+			Err(BBTourError::DegreeViolation { node: 0, degree: 3 })));
+		assert!(matches!(tour_from_edges(&[(0, 1), (1, 2), (2, 3)]).is_valid(4, false),
+			Err(BBTourError::DegreeViolation { node: 0, degree: 1 })));
+		assert!(matches!(tour_from_edges(&[(0, 1), (1, 2), (2, 0), (3, 4), (4, 5), (5, 3)]).is_valid(6, false),
+			Err(BBTourError::Subtours { count: 2 })));
+
+		// A loop counts twice towards the degree of its node
+		assert!(matches!(tour_from_edges(&[(0, 1), (1, 2), (2, 0), (1, 1)]).is_valid(3, false),
+			Err(BBTourError::DegreeViolation { node: 1, degree: 4 })));
+		assert!(matches!(tour_from_edges(&[(0, 0), (1, 2), (2, 1)]).is_valid(3, false),
+			Err(BBTourError::Subtours { count: 2 })));
+	}
+
+	#[test]
+	fn
+	errors_use_external_ids
+	()
+	{
+		// The external IDs count from 1
+		let tsp_data = TSPData::from_coordinates(&[(0.0, 0.0), (1.0, 0.0), (1.0, 1.0), (0.0, 1.0)]);
+
+		let error = tour_from_edges(&[(0, 1), (1, 2), (2, 0)]).is_valid(4, false).unwrap_err().with_external_ids(&tsp_data);
+		assert!(matches!(error, BBTourError::MissingNodes { count: 1, example: 4 }));
+		assert_eq!(error.to_string(), "Tour: 1 nodes (e.g. node 4) are not visited");
+
+		let error = tour_from_edges(&[(0, 1), (1, 2), (2, 3), (3, 0), (2, 0)]).is_valid(4, false).unwrap_err().with_external_ids(&tsp_data);
+		assert!(matches!(error, BBTourError::DegreeViolation { node: 1, degree: 3 }));
+
+		// Nodes that do not exist keep their ID
+		let error = tour_from_edges(&[(0, 7)]).is_valid(4, false).unwrap_err().with_external_ids(&tsp_data);
+		assert!(matches!(error, BBTourError::UnknownNode { node: 7 }));
 	}
-}
\ No newline at end of file
+}
@@ -0,0 +1,509 @@
+use crate::tsp_lib::node::TSPNodeID;
+
+use super::bb_flipper::BBFlip;
+use super::flipper::Flipper;
+
+/// A flipper based on a two-level list, similar to flip_two.c of Concorde
+/// The tour is divided into blocks of about sqrt(n) nodes each, which can be
+/// reversed as a whole via a flag. A flip splits at most two blocks and then
+/// reverses the order of the blocks in between, so that flips take O(sqrt(n))
+/// instead of O(n) and next, prev and sequence only take O(1).
+/// The semantics of all operations are identical to the ones of BBFlipper.
+pub struct
+BBTwoLevelFlipper
+{
+	blocks:                            Vec<BBFlipperBlock>,
+	order:                             Vec<usize>,
+	block_rank:                        Vec<usize>,
+	node_block:                        Vec<usize>,
+	node_index:                        Vec<usize>,
+	reversed:                          bool,
+	block_size:                        usize,
+	max_block_count:                   usize,
+	flips:                             Vec<BBFlip>,
+	pub total_flips:                   usize,
+	pub total_unflips:                 usize,
+}
+
+/// A block of the two-level list that stores a part of the tour
+/// If the block is reversed, its nodes are traversed from back to front
+struct
+BBFlipperBlock
+{
+	nodes:                             Vec<TSPNodeID>,
+	reversed:                          bool,
+}
+
+impl
+BBTwoLevelFlipper
+{
+
+	/// (Re-)Builds the blocks of the two-level list for the given sequence of
+	/// nodes, each block having the same size (except for the last one)
+	fn
+	build
+	(
+		&mut self,
+		sequence:                      &[TSPNodeID],
+	)
+	{
+		self.blocks.clear();
+		self.order.clear();
+		self.block_rank.clear();
+
+		for chunk in sequence.chunks(self.block_size)
+		{
+			let block_id = self.blocks.len();
+			for (index, node_id) in chunk.iter().enumerate()
+			{
+				self.node_block[*node_id] = block_id;
+				self.node_index[*node_id] = index;
+			}
+
+			self.blocks.push(BBFlipperBlock { nodes: chunk.to_vec(), reversed: false });
+			self.order.push(block_id);
+			self.block_rank.push(block_id);
+		}
+	}
+
+	/// Rebuilds the blocks once too many small blocks have been created by
+	/// splitting them during flips. The direction of the internal sequence is
+	/// kept, so that this does not change the tour in any way
+	fn
+	rebuild
+	(
+		&mut self
+	)
+	{
+		let mut sequence = Vec::with_capacity(self.node_block.len());
+		for block_id in &self.order
+		{
+			sequence.extend(self.blocks[*block_id].logical_nodes());
+		}
+
+		self.build(&sequence);
+	}
+
+	/// The position of a node inside its block, taking the reversed flag of
+	/// the block into account
+	fn
+	offset
+	(
+		&self,
+		tsp_node_id:                   &TSPNodeID,
+	)
+	-> usize
+	{
+		let block = &self.blocks[self.node_block[*tsp_node_id]];
+		if block.reversed
+		{
+			return block.nodes.len() - 1 - self.node_index[*tsp_node_id];
+		}
+		return self.node_index[*tsp_node_id];
+	}
+
+	/// The next node in the internal sequence, i.e. ignoring the global
+	/// reversed flag
+	fn
+	internal_next
+	(
+		&self,
+		tsp_node_id:                   &TSPNodeID,
+	)
+	-> TSPNodeID
+	{
+		let block_id = self.node_block[*tsp_node_id];
+		let offset = self.offset(tsp_node_id);
+
+		if offset + 1 < self.blocks[block_id].nodes.len()
+		{
+			return self.blocks[block_id].at(offset + 1);
+		}
+
+		let next_block_id = self.order[(self.block_rank[block_id] + 1) % self.order.len()];
+		return self.blocks[next_block_id].at(0);
+	}
+
+	/// The previous node in the internal sequence, i.e. ignoring the global
+	/// reversed flag
+	fn
+	internal_prev
+	(
+		&self,
+		tsp_node_id:                   &TSPNodeID,
+	)
+	-> TSPNodeID
+	{
+		let block_id = self.node_block[*tsp_node_id];
+		let offset = self.offset(tsp_node_id);
+
+		if offset > 0
+		{
+			return self.blocks[block_id].at(offset - 1);
+		}
+
+		let prev_block_id = self.order[(self.block_rank[block_id] + self.order.len() - 1) % self.order.len()];
+		return self.blocks[prev_block_id].at(self.blocks[prev_block_id].nodes.len() - 1);
+	}
+
+	/// Checks if the middle node lies on the internal sequence that starts at
+	/// the start node and ends at the end node (both included)
+	fn
+	internal_between
+	(
+		&self,
+		start:                         &TSPNodeID,
+		middle:                        &TSPNodeID,
+		end:                           &TSPNodeID,
+	)
+	-> bool
+	{
+		// The position of a node in the internal sequence, with the start node
+		// being the first one
+		let position = |tsp_node_id: &TSPNodeID|
+		{
+			let key = (self.block_rank[self.node_block[*tsp_node_id]], self.offset(tsp_node_id));
+			let start_key = (self.block_rank[self.node_block[*start]], self.offset(start));
+			return (key < start_key, key);
+		};
+
+		return position(middle) <= position(end);
+	}
+
+	/// The internal function for performing a flip, which reverses the path
+	/// from x to y. This can only be called via the publicly available flip
+	/// and unflip methods
+	fn
+	internal_flip
+	(
+		&mut self,
+		x:                             &TSPNodeID,
+		y:                             &TSPNodeID,
+	)
+	{
+		assert!(x != y);
+
+		// Special case: If y is the predecessor of x, BBFlipper swaps the two
+		// nodes instead of reversing the whole tour except for the edge (y, x)
+		let (start, end) = if (&self.next(y) == x) && (&self.next(x) != y) { (y, x) } else { (x, y) };
+
+		// The path in the internal sequence goes the other way if the tour as
+		// a whole is reversed
+		if self.reversed
+		{
+			self.internal_reverse(*end, *start);
+		}
+		else
+		{
+			self.internal_reverse(*start, *end);
+		}
+	}
+
+	/// Reverses the path from start to end in the internal sequence
+	/// Reversing the rest of the tour and the tour as a whole instead yields
+	/// the same result, which is done if that rest is shorter
+	fn
+	internal_reverse
+	(
+		&mut self,
+		start:                         TSPNodeID,
+		end:                           TSPNodeID,
+	)
+	{
+		// The number of blocks the path spans, which wraps around the whole
+		// tour if start and end are in the same block but in the wrong order
+		let block_count = self.order.len();
+		let start_rank = self.block_rank[self.node_block[start]];
+		let end_rank = self.block_rank[self.node_block[end]];
+		let spanned_blocks = if (start_rank == end_rank) && (self.offset(&end) < self.offset(&start))
+		{
+			block_count + 1
+		}
+		else
+		{
+			(end_rank + block_count - start_rank) % block_count + 1
+		};
+
+		let (start, end) = if 2 * spanned_blocks > block_count + 1
+		{
+			self.reversed = !self.reversed;
+			(self.internal_next(&end), self.internal_prev(&start))
+		}
+		else
+		{
+			(start, end)
+		};
+
+		// After toggling the reversed flag, the rest of the tour may consist
+		// of a single node only, which doesn't need to be reversed
+		if start == end
+		{
+			return;
+		}
+
+		// Make sure that the path consists of whole blocks only
+		self.split_before(&start);
+		self.split_after(&end);
+
+		// Reverse the order of these blocks as well as each of the blocks
+		let block_count = self.order.len();
+		let start_rank = self.block_rank[self.node_block[start]];
+		let end_rank = self.block_rank[self.node_block[end]];
+		let path_block_count = (end_rank + block_count - start_rank) % block_count + 1;
+
+		for i in 0..path_block_count/2
+		{
+			self.order.swap((start_rank + i) % block_count, (start_rank + path_block_count - 1 - i) % block_count);
+		}
+
+		for i in 0..path_block_count
+		{
+			let rank = (start_rank + i) % block_count;
+			let block_id = self.order[rank];
+			self.blocks[block_id].reversed = !self.blocks[block_id].reversed;
+			self.block_rank[block_id] = rank;
+		}
+
+		if self.blocks.len() > self.max_block_count
+		{
+			self.rebuild();
+		}
+	}
+
+	/// Splits the block of the given node so that the node becomes the first
+	/// one of its block
+	fn
+	split_before
+	(
+		&mut self,
+		tsp_node_id:                   &TSPNodeID,
+	)
+	{
+		let offset = self.offset(tsp_node_id);
+		if offset == 0
+		{
+			return;
+		}
+
+		let block_id = self.node_block[*tsp_node_id];
+		let mut left_nodes = self.blocks[block_id].logical_nodes();
+		let right_nodes = left_nodes.split_off(offset);
+
+		// The nodes before the given one remain in the old block
+		for (index, node_id) in left_nodes.iter().enumerate()
+		{
+			self.node_index[*node_id] = index;
+		}
+		self.blocks[block_id] = BBFlipperBlock { nodes: left_nodes, reversed: false };
+
+		// The others get moved to a new block right after the old one
+		let new_block_id = self.blocks.len();
+		for (index, node_id) in right_nodes.iter().enumerate()
+		{
+			self.node_block[*node_id] = new_block_id;
+			self.node_index[*node_id] = index;
+		}
+		self.blocks.push(BBFlipperBlock { nodes: right_nodes, reversed: false });
+		self.block_rank.push(0);
+
+		let new_rank = self.block_rank[block_id] + 1;
+		self.order.insert(new_rank, new_block_id);
+		for rank in new_rank..self.order.len()
+		{
+			self.block_rank[self.order[rank]] = rank;
+		}
+	}
+
+	/// Splits the block of the given node so that the node becomes the last
+	/// one of its block
+	fn
+	split_after
+	(
+		&mut self,
+		tsp_node_id:                   &TSPNodeID,
+	)
+	{
+		let block_id = self.node_block[*tsp_node_id];
+		if self.offset(tsp_node_id) + 1 < self.blocks[block_id].nodes.len()
+		{
+			let next_node_id = self.internal_next(tsp_node_id);
+			self.split_before(&next_node_id);
+		}
+	}
+}
+
+impl
+Flipper
+for
+BBTwoLevelFlipper
+{
+	/// Create a new flipper for a given tour that is represented in cycle form
+	/// using a vector of TSPNodeIDs
+	fn
+	new
+	(
+		tour:                          &[TSPNodeID]
+	)
+	-> Self
+	{
+		let n = tour.len();
+		let block_size = std::cmp::max(1, (n as f64).sqrt() as usize);
+		let block_count = n.div_ceil(block_size);
+
+		let mut flipper = BBTwoLevelFlipper
+		{
+			blocks:                    Vec::new(),
+			order:                     Vec::new(),
+			block_rank:                Vec::new(),
+			node_block:                vec![0; *tour.iter().max().unwrap() + 1],
+			node_index:                vec![0; *tour.iter().max().unwrap() + 1],
+			reversed:                  false,
+			block_size:                block_size,
+			max_block_count:           2 * block_count + 4,
+			flips:                     Vec::new(),
+			total_flips:               0,
+			total_unflips:             0,
+		};
+
+		flipper.build(tour);
+
+		return flipper;
+	}
+
+	/// Get the TSPNodeID of the node subsequent node in the current tour
+	fn
+	next
+	(
+		&self,
+		tsp_node_id:                   &TSPNodeID
+	)
+	-> TSPNodeID
+	{
+		if self.reversed { self.internal_prev(tsp_node_id) } else { self.internal_next(tsp_node_id) }
+	}
+
+	/// Get the TSPNodeID of the node preceeding node in the current tour
+	fn
+	prev
+	(
+		&self,
+		tsp_node_id:                   &TSPNodeID
+	)
+	-> TSPNodeID
+	{
+		if self.reversed { self.internal_next(tsp_node_id) } else { self.internal_prev(tsp_node_id) }
+	}
+
+	/// Check if three nodes form a sequence in the current tour
+	fn
+	sequence
+	(
+		&self,
+		start:                         &TSPNodeID,
+		middle:                        &TSPNodeID,
+		end:                           &TSPNodeID,
+	)
+	-> bool
+	{
+		// Just like for BBFlipper, the start node itself is not considered to
+		// be part of the sequence, unless it is also the end node
+		if middle == start && start == end { panic!("This should not happen when calling sequence"); }
+		if middle == end   { return true; }
+		if middle == start { return false; }
+		if start == end    { return true; }
+
+		if self.reversed
+		{
+			return self.internal_between(end, middle, start);
+		}
+		return self.internal_between(start, middle, end);
+	}
+
+	/// Perform a flip on the tour and keep track of it on the internal flip stack
+	fn
+	flip
+	(
+		&mut self,
+		x:                             TSPNodeID,
+		y:                             TSPNodeID,
+	)
+	{
+		self.flips.push(BBFlip { x: x, y: y });
+		self.internal_flip(&x, &y);
+		self.total_flips += 1;
+	}
+
+	/// Undo a flip. Checks that this undos the most recent flip performed by
+	/// comparing with the top of the flip stack
+	fn
+	unflip
+	(
+		&mut self,
+		x:                             TSPNodeID,
+		y:                             TSPNodeID,
+	)
+	{
+		assert!(!self.flips.is_empty());
+		assert_eq!(self.flips.last().unwrap().x, x);
+		assert_eq!(self.flips.last().unwrap().y, y);
+
+		self.flips.pop();
+
+		self.internal_flip(&y, &x);
+		self.total_unflips += 1;
+	}
+
+	/// Converts the tour currently stored by the flipper into a cycle that
+	/// is represented via a vector
+	fn
+	as_cycle
+	(
+		&self
+	)
+	-> Vec<TSPNodeID>
+	{
+		let start_node = 0;
+		let mut current_node = self.next(&start_node);
+
+		let mut cycle = Vec::with_capacity(self.node_block.len());
+		cycle.push(start_node);
+
+		while current_node != start_node
+		{
+			cycle.push(current_node);
+			current_node = self.next(&current_node);
+		}
+
+		assert_eq!(cycle.len(), self.node_block.len());
+
+		return cycle;
+	}
+}
+
+impl
+BBFlipperBlock
+{
+	/// Gets the node at the given position of the block, taking the reversed
+	/// flag into account
+	fn
+	at
+	(
+		&self,
+		offset:                        usize,
+	)
+	-> TSPNodeID
+	{
+		if self.reversed { self.nodes[self.nodes.len() - 1 - offset] } else { self.nodes[offset] }
+	}
+
+	/// Gets the nodes of the block in the order given by the reversed flag
+	fn
+	logical_nodes
+	(
+		&self
+	)
+	-> Vec<TSPNodeID>
+	{
+		if self.reversed { self.nodes.iter().rev().copied().collect() } else { self.nodes.clone() }
+	}
+}
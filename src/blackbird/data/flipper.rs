@@ -0,0 +1,264 @@
+use std::str::FromStr;
+
+use crate::tsp_lib::data::TSPData;
+use crate::tsp_lib::node::TSPNodeID;
+use crate::tsp_lib::node::TSPWeight;
+
+/// The data structures available for storing the tour during the heuristic
+#[derive(Clone, Debug, PartialEq)]
+pub enum EFlipperMethod
+{
+	/// Doubly-linked list, with O(n) flips and sequence checks
+	LinkedList,
+
+	/// Two-level doubly-linked list, with O(sqrt(n)) flips and O(1) sequence
+	/// checks, which pays off for large instances
+	TwoLevel,
+
+	/// Array with a position index, with O(n) flips but a very small overhead
+	/// otherwise, which pays off for small instances
+	Array,
+
+	/// Splay tree with reversal flags, with O(log(n)) amortized time for all
+	/// operations
+	Splay,
+}
+
+impl
+FromStr
+for
+EFlipperMethod
+{
+	type Err = ();
+
+	/// Allows the creation of flipper method enum values from given strings
+	/// as provided for example by the command line interface
+	fn
+	from_str
+	(
+		s: &str
+	)
+	-> Result<Self, Self::Err>
+	{
+		match s.to_lowercase().as_str().chars().nth(0).unwrap_or(' ')
+		{
+			'l'                        => Ok(EFlipperMethod::LinkedList),
+			't'                        => Ok(EFlipperMethod::TwoLevel),
+			'a'                        => Ok(EFlipperMethod::Array),
+			's'                        => Ok(EFlipperMethod::Splay),
+			_                          => Err(())
+		}
+	}
+}
+
+/// The operations the heuristic needs for storing the tour and modifying it
+/// via flips. Implemented by several data structures that differ in their
+/// runtime, but must have identical semantics, so that the heuristic yields
+/// the same results regardless of the data structure that is used.
+pub trait
+Flipper
+{
+	/// Create a new flipper for a given tour that is represented in cycle form
+	/// using a vector of TSPNodeIDs
+	fn
+	new
+	(
+		tour:                          &[TSPNodeID]
+	)
+	-> Self
+	where
+		Self: Sized;
+
+	/// Get the TSPNodeID of the node subsequent node in the current tour
+	fn
+	next
+	(
+		&self,
+		tsp_node_id:                   &TSPNodeID
+	)
+	-> TSPNodeID;
+
+	/// Get the TSPNodeID of the node preceeding node in the current tour
+	fn
+	prev
+	(
+		&self,
+		tsp_node_id:                   &TSPNodeID
+	)
+	-> TSPNodeID;
+
+	/// Check if three nodes form a sequence in the current tour, i.e. if the
+	/// middle node is reached before the end node when starting at the start
+	/// node (which itself does not count as part of the sequence)
+	fn
+	sequence
+	(
+		&self,
+		start:                         &TSPNodeID,
+		middle:                        &TSPNodeID,
+		end:                           &TSPNodeID,
+	)
+	-> bool;
+
+	/// Perform a flip on the tour, which reverses the path from x to y, and 
+	/// keep track of it on the internal flip stack. If y is the predecessor of
+	/// x, the two nodes get swapped instead.
+	fn
+	flip
+	(
+		&mut self,
+		x:                             TSPNodeID,
+		y:                             TSPNodeID,
+	);
+
+	/// Undo a flip. Checks that this undos the most recent flip performed by
+	/// comparing with the top of the flip stack
+	fn
+	unflip
+	(
+		&mut self,
+		x:                             TSPNodeID,
+		y:                             TSPNodeID,
+	);
+
+	/// Converts the tour currently stored by the flipper into a cycle that
+	/// is represented via a vector, starting with node 0
+	fn
+	as_cycle
+	(
+		&self
+	)
+	-> Vec<TSPNodeID>;
+
+	/// Computes the cost of the tour currently stored by the flipper
+	fn
+	cost
+	(
+		&self,
+		tsp_data:                      &TSPData,
+	)
+	-> TSPWeight
+	{
+		let cycle = self.as_cycle();
+		let mut length = 0.0;
+		for i in 0..cycle.len()
+		{
+			length += tsp_data.get_distance_between_via_id(cycle[i], cycle[(i+1)%tsp_data.n]);
+		}
+
+		return length;
+	}
+}
+
+#[cfg(test)]
+mod tests
+{
+	use rand::Rng;
+	use rand::rngs::StdRng;
+	use rand::seq::SliceRandom;
+
+	use crate::blackbird::data::bb_array_flipper::BBArrayFlipper;
+	use crate::blackbird::data::bb_flipper::BBFlipper;
+	use crate::blackbird::data::bb_splay_flipper::BBSplayFlipper;
+	use crate::blackbird::data::bb_two_level_flipper::BBTwoLevelFlipper;
+
+	use super::*;
+
+	/// Checks that the given flippers store the same tour
+	fn
+	assert_same_tour<F: Flipper>
+	(
+		reference:                     &BBFlipper,
+		flipper:                       &F,
+		n:                             usize,
+		random_generator:              &mut StdRng,
+	)
+	{
+		assert_eq!(reference.as_cycle(), flipper.as_cycle());
+
+		for node_id in 0..n
+		{
+			assert_eq!(reference.next(&node_id), flipper.next(&node_id));
+			assert_eq!(reference.prev(&node_id), flipper.prev(&node_id));
+		}
+
+		for _ in 0..n
+		{
+			let start  = random_generator.gen_range(0..n);
+			let middle = random_generator.gen_range(0..n);
+			let end    = random_generator.gen_range(0..n);
+			if start == middle && middle == end
+			{
+				continue;
+			}
+			assert_eq!(reference.sequence(&start, &middle, &end), flipper.sequence(&start, &middle, &end));
+		}
+	}
+
+	/// Applies the same random flips to the given flipper and to a BBFlipper
+	/// and compares them after each flip, and again while undoing the flips
+	fn
+	compare_with_linked_list<F: Flipper>
+	()
+	{
+		let mut random_generator: StdRng = rand_seeder::Seeder::from(7).make_rng();
+
+		for n in [5, 8, 13, 50, 120]
+		{
+			let mut tour = (0..n).collect::<Vec<TSPNodeID>>();
+			tour.shuffle(&mut random_generator);
+
+			let mut reference = BBFlipper::new(&tour);
+			let mut flipper = F::new(&tour);
+			let mut flips = Vec::new();
+
+			for _ in 0..200
+			{
+				let x = random_generator.gen_range(0..n);
+				let y = random_generator.gen_range(0..n);
+				if x == y
+				{
+					continue;
+				}
+
+				reference.flip(x, y);
+				flipper.flip(x, y);
+				flips.push((x, y));
+				assert_same_tour(&reference, &flipper, n, &mut random_generator);
+			}
+
+			while let Some((x, y)) = flips.pop()
+			{
+				reference.unflip(x, y);
+				flipper.unflip(x, y);
+				assert_same_tour(&reference, &flipper, n, &mut random_generator);
+			}
+
+			assert_eq!(flipper.as_cycle(), BBFlipper::new(&tour).as_cycle());
+		}
+	}
+
+	#[test]
+	fn
+	array_flipper_matches_linked_list
+	()
+	{
+		compare_with_linked_list::<BBArrayFlipper>();
+	}
+
+	#[test]
+	fn
+	two_level_flipper_matches_linked_list
+	()
+	{
+		compare_with_linked_list::<BBTwoLevelFlipper>();
+	}
+
+	#[test]
+	fn
+	splay_flipper_matches_linked_list
+	()
+	{
+		compare_with_linked_list::<BBSplayFlipper>();
+	}
+}
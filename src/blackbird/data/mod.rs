@@ -1,4 +1,9 @@
 pub mod bb_edge;
 pub mod bb_tour;
 pub mod bb_flipper;
-pub mod bb_data;
\ No newline at end of file
+pub mod bb_two_level_flipper;
+pub mod bb_array_flipper;
+pub mod bb_splay_flipper;
+pub mod flipper;
+pub mod bb_data;pub mod bb_search;
+pub mod bb_spanning_tree;
@@ -0,0 +1 @@
+pub mod triangulation;
\ No newline at end of file
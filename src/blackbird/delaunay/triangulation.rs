@@ -0,0 +1,444 @@
+use std::collections::HashMap;
+
+use crate::tsp_lib::data::TSPData;
+use crate::tsp_lib::node::*;
+
+/// Marks a missing neighbor of a triangle, i.e. an edge of the super triangle
+const NO_TRIANGLE: usize = usize::MAX;
+
+/// The Delaunay triangulation of the nodes of a 2-dimensional instance, 
+/// computed incrementally using the Bowyer-Watson algorithm
+/// The edges of the triangulation form a sparse set of good edges that still
+/// contains almost all edges of optimal tours
+pub struct
+DelaunayTriangulation
+{
+	/// The coordinates of all nodes followed by those of the super triangle
+	points:                            Vec<(TSPWeight, TSPWeight)>,
+
+	/// The three corners of each triangle in counterclockwise order
+	triangles:                         Vec<[usize; 3]>,
+
+	/// For each triangle the neighbor opposite to each of its corners
+	neighbors:                         Vec<[usize; 3]>,
+
+	/// Triangles get replaced during the insertion of a point
+	alive:                             Vec<bool>,
+
+	/// Nodes with the same coordinates as an earlier node, which are not part
+	/// of the triangulation, together with that earlier node
+	duplicates:                        Vec<(TSPNodeID, TSPNodeID)>,
+}
+
+impl
+DelaunayTriangulation
+{
+	/// Computes the Delaunay triangulation for the nodes of the given data
+	/// Only the x and y coordinates of the nodes are taken into account
+	pub fn
+	new
+	(
+		tsp_data:                      &TSPData
+	)
+	-> DelaunayTriangulation
+	{
+		let mut points = tsp_data.nodes.iter().map(|node| (node.x, node.y)).collect::<Vec<(TSPWeight, TSPWeight)>>();
+
+		// A triangle that contains all the nodes with lots of room to spare
+		let min_x = points.iter().map(|point| point.0).fold(TSPWeight::MAX, TSPWeight::min);
+		let max_x = points.iter().map(|point| point.0).fold(TSPWeight::MIN, TSPWeight::max);
+		let min_y = points.iter().map(|point| point.1).fold(TSPWeight::MAX, TSPWeight::min);
+		let max_y = points.iter().map(|point| point.1).fold(TSPWeight::MIN, TSPWeight::max);
+		let size = (max_x - min_x).max(max_y - min_y).max(1.0);
+		let center = ((min_x + max_x) / 2.0, (min_y + max_y) / 2.0);
+
+		let n = points.len();
+		points.push((center.0 - 1000.0 * size, center.1 - 1000.0 * size));
+		points.push((center.0 + 1000.0 * size, center.1 - 1000.0 * size));
+		points.push((center.0,               center.1 + 1000.0 * size));
+
+		let mut triangulation = DelaunayTriangulation
+		{
+			points:                    points,
+			triangles:                 vec![[n, n+1, n+2]],
+			neighbors:                 vec![[NO_TRIANGLE; 3]],
+			alive:                     vec![true],
+			duplicates:                Vec::new(),
+		};
+
+		// Insert the nodes in an order in which consecutive nodes are close to
+		// each other, which keeps the walks to the next node short
+		// Nodes with the same coordinates as the previous one get skipped
+		let mut last_triangle = 0;
+		let mut previous: Option<TSPNodeID> = None;
+		for node_id in triangulation.insertion_order(n, min_x, max_x)
+		{
+			if let Some(previous_id) = previous
+			{
+				if triangulation.points[previous_id] == triangulation.points[node_id]
+				{
+					triangulation.duplicates.push((node_id, previous_id));
+					continue;
+				}
+			}
+
+			last_triangle = triangulation.insert(node_id, last_triangle);
+			previous = Some(node_id);
+		}
+
+		return triangulation;
+	}
+
+	/// Sorts the nodes into vertical strips that are traversed alternating
+	/// upwards and downwards. Equal points end up next to each other
+	fn
+	insertion_order
+	(
+		&self,
+		n:                             usize,
+		min_x:                         TSPWeight,
+		max_x:                         TSPWeight,
+	)
+	-> Vec<TSPNodeID>
+	{
+		let strip_count = ((n as f64 / 2.0).sqrt().ceil() as usize).max(1);
+		let strip_width = ((max_x - min_x) / strip_count as f64).max(TSPWeight::MIN_POSITIVE);
+		let strip = |node_id: TSPNodeID| std::cmp::min(((self.points[node_id].0 - min_x) / strip_width) as usize, strip_count - 1);
+
+		let mut order = (0..n).collect::<Vec<TSPNodeID>>();
+		order.sort_by(|a, b|
+		{
+			let (strip_a, strip_b) = (strip(*a), strip(*b));
+			if strip_a != strip_b
+			{
+				return strip_a.cmp(&strip_b);
+			}
+
+			let by_y = self.points[*a].1.partial_cmp(&self.points[*b].1).unwrap()
+				.then(self.points[*a].0.partial_cmp(&self.points[*b].0).unwrap());
+			return if strip_a % 2 == 0 { by_y } else { by_y.reverse() };
+		});
+
+		return order;
+	}
+
+	/// Positive if the three points are in counterclockwise order, negative
+	/// if they are in clockwise order and zero if they are collinear
+	fn
+	orientation
+	(
+		&self,
+		a:                             usize,
+		b:                             usize,
+		c:                             usize,
+	)
+	-> TSPWeight
+	{
+		let (ax, ay) = self.points[a];
+		let (bx, by) = self.points[b];
+		let (cx, cy) = self.points[c];
+
+		return (bx - ax) * (cy - ay) - (by - ay) * (cx - ax);
+	}
+
+	/// Checks if the point lies strictly inside of the circumcircle of the
+	/// given triangle
+	fn
+	in_circumcircle
+	(
+		&self,
+		triangle:                      usize,
+		point:                         usize,
+	)
+	-> bool
+	{
+		let (px, py) = self.points[point];
+		let [a, b, c] = self.triangles[triangle];
+		let (ax, ay) = (self.points[a].0 - px, self.points[a].1 - py);
+		let (bx, by) = (self.points[b].0 - px, self.points[b].1 - py);
+		let (cx, cy) = (self.points[c].0 - px, self.points[c].1 - py);
+
+		let determinant = 
+			  (ax * ax + ay * ay) * (bx * cy - cx * by)
+			- (bx * bx + by * by) * (ax * cy - cx * ay)
+			+ (cx * cx + cy * cy) * (ax * by - bx * ay);
+
+		return determinant > 0.0;
+	}
+
+	/// Finds the triangle that contains the point by walking towards it, 
+	/// starting at the given triangle
+	fn
+	locate
+	(
+		&self,
+		point:                         usize,
+		start:                         usize,
+	)
+	-> usize
+	{
+		let mut triangle = start;
+		let mut steps = 0;
+
+		'walk: loop
+		{
+			// Vary the order in which the edges are checked, so that the walk
+			// can not run in circles
+			steps += 1;
+			for k in 0..3
+			{
+				let i = (k + steps) % 3;
+				let a = self.triangles[triangle][(i + 1) % 3];
+				let b = self.triangles[triangle][(i + 2) % 3];
+
+				if self.orientation(a, b, point) < 0.0 && self.neighbors[triangle][i] != NO_TRIANGLE
+				{
+					triangle = self.neighbors[triangle][i];
+					continue 'walk;
+				}
+			}
+
+			return triangle;
+		}
+	}
+
+	/// Inserts a point by removing all triangles whose circumcircle contains
+	/// it and connecting the point with the boundary of the resulting cavity
+	/// Returns one of the newly created triangles
+	fn
+	insert
+	(
+		&mut self,
+		point:                         usize,
+		start:                         usize,
+	)
+	-> usize
+	{
+		let first = self.locate(point, start);
+
+		// Collect the triangles whose circumcircle contains the point, which
+		// form a connected area around the triangle containing the point
+		let mut is_bad: HashMap<usize, bool> = HashMap::new();
+		let mut bad_triangles = vec![first];
+		let mut stack = vec![first];
+		is_bad.insert(first, true);
+
+		while let Some(triangle) = stack.pop()
+		{
+			for neighbor in self.neighbors[triangle]
+			{
+				if neighbor == NO_TRIANGLE || is_bad.contains_key(&neighbor)
+				{
+					continue;
+				}
+
+				let bad = self.in_circumcircle(neighbor, point);
+				is_bad.insert(neighbor, bad);
+				if bad
+				{
+					bad_triangles.push(neighbor);
+					stack.push(neighbor);
+				}
+			}
+		}
+
+		// Connect the point with each edge of the boundary of the cavity
+		let mut triangle_starting_at: HashMap<usize, usize> = HashMap::new();
+		let mut new_triangles = Vec::new();
+
+		for &triangle in &bad_triangles
+		{
+			self.alive[triangle] = false;
+
+			for i in 0..3
+			{
+				let outside = self.neighbors[triangle][i];
+				if outside != NO_TRIANGLE && is_bad[&outside]
+				{
+					continue;
+				}
+
+				let a = self.triangles[triangle][(i + 1) % 3];
+				let b = self.triangles[triangle][(i + 2) % 3];
+
+				let new_triangle = self.triangles.len();
+				self.triangles.push([a, b, point]);
+				self.neighbors.push([NO_TRIANGLE, NO_TRIANGLE, outside]);
+				self.alive.push(true);
+
+				if outside != NO_TRIANGLE
+				{
+					for j in 0..3
+					{
+						if self.neighbors[outside][j] == triangle
+						{
+							self.neighbors[outside][j] = new_triangle;
+						}
+					}
+				}
+
+				triangle_starting_at.insert(a, new_triangle);
+				new_triangles.push(new_triangle);
+			}
+		}
+
+		// The new triangles (a, b, point) and (b, c, point) share the edge
+		// from b to the point
+		for &new_triangle in &new_triangles
+		{
+			let b = self.triangles[new_triangle][1];
+			let next_triangle = triangle_starting_at[&b];
+			self.neighbors[new_triangle][0] = next_triangle;
+			self.neighbors[next_triangle][1] = new_triangle;
+		}
+
+		return new_triangles[0];
+	}
+
+	/// Gets all edges of the triangulation between two nodes of the instance
+	/// Nodes that have been skipped due to having the same coordinates as 
+	/// another node are connected to that node
+	pub fn
+	edges
+	(
+		&self
+	)
+	-> Vec<(TSPNodeID, TSPNodeID)>
+	{
+		let n = self.points.len() - 3;
+		let mut edges = self.duplicates.clone();
+
+		for triangle in 0..self.triangles.len()
+		{
+			if !self.alive[triangle]
+			{
+				continue;
+			}
+
+			for i in 0..3
+			{
+				let a = self.triangles[triangle][(i + 1) % 3];
+				let b = self.triangles[triangle][(i + 2) % 3];
+				let neighbor = self.neighbors[triangle][i];
+
+				// Each edge between two triangles is only collected once
+				if a < n && b < n && (neighbor == NO_TRIANGLE || neighbor > triangle)
+				{
+					edges.push((a, b));
+				}
+			}
+		}
+
+		return edges;
+	}
+}
+
+#[cfg(test)]
+mod tests
+{
+	use rand::Rng;
+	use rand::rngs::StdRng;
+
+	use super::*;
+
+	/// Computes the length of a minimum spanning tree using Prim's algorithm,
+	/// either on the complete graph or only on the given edges
+	fn
+	spanning_tree_length
+	(
+		tsp_data:                      &TSPData,
+		edges:                         Option<&[(TSPNodeID, TSPNodeID)]>,
+	)
+	-> TSPWeight
+	{
+		let n = tsp_data.n;
+		let mut allowed = vec![vec![edges.is_none(); n]; n];
+		for &(a, b) in edges.unwrap_or(&[])
+		{
+			allowed[a][b] = true;
+			allowed[b][a] = true;
+		}
+
+		let mut in_tree = vec![false; n];
+		let mut distance = vec![TSPWeight::INFINITY; n];
+		let mut length = 0.0;
+		distance[0] = 0.0;
+
+		for _ in 0..n
+		{
+			let u = (0..n).filter(|u| !in_tree[*u]).min_by(|a, b| distance[*a].partial_cmp(&distance[*b]).unwrap()).unwrap();
+			assert!(distance[u].is_finite(), "The edges do not connect node {}", u);
+			in_tree[u] = true;
+			length += distance[u];
+
+			for v in (0..n).filter(|v| !in_tree[*v] && allowed[u][*v])
+			{
+				distance[v] = distance[v].min(tsp_data.get_distance_between_via_id(u, v));
+			}
+		}
+
+		return length;
+	}
+
+	#[test]
+	fn
+	triangulation_is_delaunay
+	()
+	{
+		let mut random_generator: StdRng = rand_seeder::Seeder::from(42).make_rng();
+
+		for _ in 0..50
+		{
+			let n = random_generator.gen_range(3..=60);
+			let coordinates = (0..n)
+				.map(|_| (random_generator.gen_range(0.0..1000.0), random_generator.gen_range(0.0..1000.0)))
+				.collect::<Vec<(TSPWeight, TSPWeight)>>();
+			let tsp_data = TSPData::from_coordinates(&coordinates);
+			let triangulation = DelaunayTriangulation::new(&tsp_data);
+
+			// No node lies inside of the circumcircle of any triangle
+			for triangle in (0..triangulation.triangles.len()).filter(|triangle| triangulation.alive[*triangle])
+			{
+				assert!(triangulation.orientation(triangulation.triangles[triangle][0], triangulation.triangles[triangle][1], triangulation.triangles[triangle][2]) > 0.0);
+				for point in 0..n
+				{
+					assert!(!triangulation.in_circumcircle(triangle, point));
+				}
+			}
+
+			// Points in general position have at most 3n - 6 edges, and the 
+			// Delaunay triangulation contains a minimum spanning tree
+			let edges = triangulation.edges();
+			assert!(edges.len() <= 3 * n - 6);
+			assert!((spanning_tree_length(&tsp_data, Some(&edges)) - spanning_tree_length(&tsp_data, None)).abs() < 1e-6);
+		}
+	}
+
+	#[test]
+	fn
+	handles_duplicates_and_collinear_nodes
+	()
+	{
+		let mut random_generator: StdRng = rand_seeder::Seeder::from(7).make_rng();
+
+		for _ in 0..50
+		{
+			// Coordinates on a small grid contain duplicates as well as lots
+			// of collinear and cocircular nodes
+			let n = random_generator.gen_range(2..=60);
+			let coordinates = (0..n)
+				.map(|_| (random_generator.gen_range(0..5) as TSPWeight, random_generator.gen_range(0..5) as TSPWeight))
+				.collect::<Vec<(TSPWeight, TSPWeight)>>();
+			let tsp_data = TSPData::from_coordinates(&coordinates);
+			let edges = DelaunayTriangulation::new(&tsp_data).edges();
+
+			assert!(edges.iter().all(|(a, b)| a != b && *a < n && *b < n));
+			assert!((spanning_tree_length(&tsp_data, Some(&edges)) - spanning_tree_length(&tsp_data, None)).abs() < 1e-6);
+		}
+
+		// All nodes on a single line
+		let tsp_data = TSPData::from_coordinates(&(0..10).map(|i| (i as TSPWeight, 2.0 * i as TSPWeight)).collect::<Vec<(TSPWeight, TSPWeight)>>());
+		let edges = DelaunayTriangulation::new(&tsp_data).edges();
+		assert!((spanning_tree_length(&tsp_data, Some(&edges)) - spanning_tree_length(&tsp_data, None)).abs() < 1e-6);
+	}
+}
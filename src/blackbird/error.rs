@@ -0,0 +1,78 @@
+use std::fmt;
+
+use crate::blackbird::data::bb_tour::BBTourError;
+use crate::tsp_lib::error::TSPLibError;
+
+/// The errors that can occur while solving a TSP instance, which are caused
+/// by the given instance or configuration instead of ending up as panic deep
+/// inside the heuristic
+#[derive(Debug)]
+pub enum
+SolverError
+{
+	TooFewNodes { n: usize },
+	InvalidLKParams { reason: String },
+	InvalidTourFile(TSPLibError),
+	InvalidInitialTour(BBTourError),
+}
+
+impl
+fmt::Display
+for
+SolverError
+{
+	fn
+	fmt
+	(
+		&self,
+		f: &mut fmt::Formatter<'_>
+	)
+	-> fmt::Result
+	{
+		match self
+		{
+			SolverError::TooFewNodes { n }
+				=> write!(f, "Solver: The instance needs at least 2 nodes but has {}", n),
+			SolverError::InvalidLKParams { reason }
+				=> write!(f, "Solver: Invalid LK parameters: {}", reason),
+			SolverError::InvalidTourFile(error)
+				=> write!(f, "Solver: Could not read the initial tour: {}", error),
+			SolverError::InvalidInitialTour(error)
+				=> write!(f, "Solver: Could not create a valid initial tour: {}", error),
+		}
+	}
+}
+
+impl std::error::Error for SolverError {}
+
+impl
+From<BBTourError>
+for
+SolverError
+{
+	fn
+	from
+	(
+		error: BBTourError
+	)
+	-> Self
+	{
+		SolverError::InvalidInitialTour(error)
+	}
+}
+
+impl
+From<TSPLibError>
+for
+SolverError
+{
+	fn
+	from
+	(
+		error: TSPLibError
+	)
+	-> Self
+	{
+		SolverError::InvalidTourFile(error)
+	}
+}
@@ -0,0 +1,521 @@
+use std::collections::HashMap;
+use std::time::Instant;
+
+use crate::blackbird::data::bb_data::BBData;
+use crate::blackbird::data::bb_spanning_tree::NO_PARENT;
+use crate::tsp_lib::node::TSPNodeID;
+use crate::tsp_lib::node::TSPWeight;
+
+/// The maximum number of iterations of the subgradient optimization
+const ASCENT_ITERATIONS_MAX: usize = 1000;
+
+/// The number of iterations of the first period of the subgradient
+/// optimization, unless the instance has fewer than twice as many nodes
+const ASCENT_PERIOD_MAX: usize = 100;
+
+impl
+BBData<'_>
+{
+	/// Computes the good edges based on alpha-nearness as introduced by LKH
+	/// The alpha value of an edge is the increase in length of a minimum
+	/// 1-tree that is required to contain this edge. The lengths are modified
+	/// by node penalties that are optimized via subgradient optimization to
+	/// bring the 1-tree closer to a tour (i.e. all nodes having degree 2)
+	/// For each node, the edges with the smallest alpha values are kept
+	pub fn
+	alpha_nearness_edges
+	(
+		&self
+	)
+	-> HashMap<TSPNodeID, Vec<TSPNodeID>>
+	{
+		// Start time measurement
+		let time_measurement_start = Instant::now();
+
+		let n = self.tsp_data.n;
+		let mut sparse_edge_map: HashMap<TSPNodeID, Vec<TSPNodeID>> = HashMap::new();
+		if n < 3
+		{
+			for node_id in 0..n
+			{
+				sparse_edge_map.insert(node_id, (0..n).filter(|other_id| other_id != &node_id).collect());
+			}
+			return sparse_edge_map;
+		}
+
+		let adjacency = self.one_tree_adjacency();
+		let penalties = self.subgradient_optimization(&adjacency);
+		if (self.config.verbose)
+		{
+			println!("1-tree lower bound : {}", self.minimum_one_tree(&penalties, &adjacency).0);
+		}
+		let tree = self.minimum_spanning_tree(&penalties, &adjacency).unwrap();
+		let cost = |a: TSPNodeID, b: TSPNodeID| self.tsp_data.get_distance_between_via_id(a, b) + penalties[a] + penalties[b];
+
+		// For each node, compute the largest cost on the tree path to all the
+		// other nodes (beta), going through the nodes in tree order. The alpha
+		// value of an edge is its cost minus the beta value of its end node
+		let mut beta = vec![0.0; n];
+		let mut mark = vec![NO_PARENT; n];
+		let count = std::cmp::min(self.config.alpha_nearness_count, n - 1);
+
+		for node_id in 0..n
+		{
+			// The sparse edge set is of no use once the time limit is reached,
+			// as CLK gets skipped then
+			if self.time_limit_reached()
+			{
+				break;
+			}
+
+			beta[node_id] = TSPWeight::MIN;
+			let mut current = node_id;
+			while tree.parent[current] != NO_PARENT
+			{
+				let parent = tree.parent[current];
+				beta[parent] = beta[current].max(cost(current, parent));
+				mark[parent] = node_id;
+				current = parent;
+			}
+			mark[node_id] = node_id;
+
+			// The currently best candidates, sorted by their alpha value and
+			// their cost to break ties
+			let mut candidates: Vec<(TSPWeight, TSPWeight, TSPNodeID)> = Vec::with_capacity(count + 1);
+
+			for &other_node_id in &tree.order
+			{
+				if mark[other_node_id] != node_id
+				{
+					let parent = tree.parent[other_node_id];
+					beta[other_node_id] = beta[parent].max(cost(other_node_id, parent));
+				}
+				if other_node_id == node_id
+				{
+					continue;
+				}
+
+				let edge_cost = cost(node_id, other_node_id);
+				let alpha = edge_cost - beta[other_node_id];
+
+				if candidates.len() < count || (alpha, edge_cost) < (candidates[count-1].0, candidates[count-1].1)
+				{
+					let position = candidates.partition_point(|&(a, c, _)| (a, c) <= (alpha, edge_cost));
+					candidates.insert(position, (alpha, edge_cost, other_node_id));
+					candidates.truncate(count);
+				}
+			}
+
+			sparse_edge_map.insert(node_id, candidates.iter().map(|&(_, _, other_node_id)| other_node_id).collect());
+		}
+
+		if (self.config.verbose)
+		{
+			println!("Alpha-nearness computation time : {}", (time_measurement_start.elapsed().as_micros() as f64) / 1000000.0);
+		}
+
+		return sparse_edge_map;
+	}
+
+	/// The spanning trees during the subgradient optimization only use the
+	/// quadrant nearest edges (and the Delaunay edges if available) as long
+	/// as these connect all of the nodes. Returns None if all edges are used
+	fn
+	one_tree_adjacency
+	(
+		&self
+	)
+	-> Option<Vec<Vec<TSPNodeID>>>
+	{
+		let n = self.tsp_data.n;
+		let mut adjacency = vec![Vec::new(); n];
+		let mut sparse_edge_maps = vec![self.quadrant_nearest_edges()];
+		if self.tsp_data.distance.is_geometric() && self.tsp_data.distance.dimensions() == 2
+		{
+			sparse_edge_maps.push(self.delaunay_edges());
+		}
+		for (node_id, other_nodes) in sparse_edge_maps.into_iter().flatten()
+		{
+			for other_node_id in other_nodes
+			{
+				if !adjacency[node_id].contains(&other_node_id)
+				{
+					adjacency[node_id].push(other_node_id);
+					adjacency[other_node_id].push(node_id);
+				}
+			}
+		}
+		let adjacency = Some(adjacency);
+		let connected = self.minimum_spanning_tree(&vec![0.0; n], &adjacency).is_some();
+
+		return if connected { adjacency } else { None };
+	}
+
+	/// Optimizes the node penalties such that the length of the minimum 1-tree
+	/// (a lower bound for the length of an optimal tour) gets maximized, using
+	/// the step size schedule of LKH. Returns the best penalties found
+	fn
+	subgradient_optimization
+	(
+		&self,
+		adjacency:                     &Option<Vec<Vec<TSPNodeID>>>,
+	)
+	-> Vec<TSPWeight>
+	{
+		let n = self.tsp_data.n;
+		let mut penalties = vec![0.0; n];
+		let mut best_penalties = penalties.clone();
+		let mut last_subgradient = vec![0i64; n];
+
+		let (mut best_bound, degrees) = self.minimum_one_tree(&penalties, adjacency);
+		if degrees.iter().all(|degree| *degree == 2)
+		{
+			return best_penalties;
+		}
+
+		// The initial step size is relative to the average edge cost
+		let mut step = 0.01 * best_bound / n as TSPWeight;
+		let mut period = (n / 2).clamp(1, ASCENT_PERIOD_MAX);
+		let mut initial_phase = true;
+		let mut iterations = 0;
+
+		while step > 0.0 && period > 0 && iterations < ASCENT_ITERATIONS_MAX && !self.time_limit_reached()
+		{
+			let mut p = 1;
+			while p <= period && iterations < ASCENT_ITERATIONS_MAX
+			{
+				iterations += 1;
+
+				let (bound, degrees) = self.minimum_one_tree(&penalties, adjacency);
+				if bound > best_bound
+				{
+					best_bound = bound;
+					best_penalties = penalties.clone();
+
+					// Increase the step size as long as this improves the
+					// bound in the initial phase, and extend the period if it
+					// still improves at its end
+					if initial_phase
+					{
+						step *= 2.0;
+					}
+					if p == period
+					{
+						period *= 2;
+					}
+				}
+				else if initial_phase && p > period / 2
+				{
+					initial_phase = false;
+					p = 0;
+					step *= 0.75;
+				}
+
+				// The 1-tree is a tour, so the bound can not be improved
+				if degrees.iter().all(|degree| *degree == 2)
+				{
+					return penalties;
+				}
+
+				for node_id in 0..n
+				{
+					let subgradient = degrees[node_id] as i64 - 2;
+					penalties[node_id] += step * (0.7 * subgradient as TSPWeight + 0.3 * last_subgradient[node_id] as TSPWeight);
+					last_subgradient[node_id] = subgradient;
+				}
+
+				p += 1;
+			}
+
+			period /= 2;
+			step /= 2.0;
+		}
+
+		return best_penalties;
+	}
+
+	/// Computes a minimum 1-tree for the given penalties, which consists of a
+	/// minimum spanning tree and the second cheapest edge of one of its leaves
+	/// (choosing the leaf that maximizes the length of the 1-tree)
+	/// Returns the lower bound given by the 1-tree and the degrees of the nodes
+	fn
+	minimum_one_tree
+	(
+		&self,
+		penalties:                     &[TSPWeight],
+		adjacency:                     &Option<Vec<Vec<TSPNodeID>>>,
+	)
+	-> (TSPWeight, Vec<usize>)
+	{
+		let n = self.tsp_data.n;
+		let tree = self.minimum_spanning_tree(penalties, adjacency).unwrap();
+		let cost = |a: TSPNodeID, b: TSPNodeID| self.tsp_data.get_distance_between_via_id(a, b) + penalties[a] + penalties[b];
+
+		let mut degrees = vec![0; n];
+		for node_id in 0..n
+		{
+			if tree.parent[node_id] != NO_PARENT
+			{
+				degrees[node_id] += 1;
+				degrees[tree.parent[node_id]] += 1;
+			}
+		}
+
+		// Find the leaf whose cheapest edge not in the tree is the most costly
+		let mut special: Option<(TSPWeight, TSPNodeID, TSPNodeID)> = None;
+		for leaf in (0..n).filter(|node_id| degrees[*node_id] == 1)
+		{
+			let tree_neighbor = if tree.parent[leaf] != NO_PARENT { tree.parent[leaf] }
+				else { (0..n).find(|node_id| tree.parent[*node_id] == leaf).unwrap() };
+
+			let mut cheapest: Option<(TSPWeight, TSPNodeID)> = None;
+			let mut consider = |other_node_id: TSPNodeID|
+			{
+				if other_node_id != leaf && other_node_id != tree_neighbor
+				{
+					let edge_cost = cost(leaf, other_node_id);
+					if cheapest.is_none() || edge_cost < cheapest.unwrap().0
+					{
+						cheapest = Some((edge_cost, other_node_id));
+					}
+				}
+			};
+			match adjacency
+			{
+				Some(adjacency) => adjacency[leaf].iter().for_each(|other_node_id| consider(*other_node_id)),
+				None            => (0..n).for_each(consider),
+			}
+
+			if let Some((edge_cost, other_node_id)) = cheapest
+			{
+				if special.is_none() || edge_cost > special.unwrap().0
+				{
+					special = Some((edge_cost, leaf, other_node_id));
+				}
+			}
+		}
+
+		let mut length = tree.length - 2.0 * penalties.iter().sum::<TSPWeight>();
+		if let Some((edge_cost, leaf, other_node_id)) = special
+		{
+			length += edge_cost;
+			degrees[leaf] += 1;
+			degrees[other_node_id] += 1;
+		}
+
+		return (length, degrees);
+	}
+}
+
+#[cfg(test)]
+mod tests
+{
+	use rand::Rng;
+	use rand::rngs::StdRng;
+
+	use crate::blackbird::solver::SolverConfig;
+	use crate::tsp_lib::data::TSPData;
+
+	use super::*;
+
+	/// Creates an instance with random coordinates
+	fn
+	random_instance
+	(
+		n:                             usize,
+		random_generator:              &mut StdRng,
+	)
+	-> TSPData
+	{
+		let coordinates = (0..n)
+			.map(|_| (random_generator.gen_range(0.0..100.0), random_generator.gen_range(0.0..100.0)))
+			.collect::<Vec<(TSPWeight, TSPWeight)>>();
+		return TSPData::from_coordinates(&coordinates);
+	}
+
+	/// Creates the data of the solver for the given instance, including the
+	/// KD tree required for the sparse edge sets
+	fn
+	bb_data
+	(
+		tsp_data:                      &TSPData,
+	)
+	-> BBData<'_>
+	{
+		let config = SolverConfig { random_generator_seed: 1, ..SolverConfig::default() };
+		let mut bb_data = BBData::new(tsp_data, config);
+		bb_data.construct_kd_tree();
+		return bb_data;
+	}
+
+	/// Computes the length of an optimal tour by trying all of them
+	fn
+	optimal_tour_length
+	(
+		tsp_data:                      &TSPData,
+	)
+	-> TSPWeight
+	{
+		fn
+		extend
+		(
+			tsp_data:                  &TSPData,
+			tour:                      &mut Vec<TSPNodeID>,
+			used:                      &mut Vec<bool>,
+			length:                    TSPWeight,
+		)
+		-> TSPWeight
+		{
+			let last = *tour.last().unwrap();
+			if tour.len() == tsp_data.n
+			{
+				return length + tsp_data.get_distance_between_via_id(last, tour[0]);
+			}
+
+			let mut best = TSPWeight::MAX;
+			for node_id in 0..tsp_data.n
+			{
+				if !used[node_id]
+				{
+					used[node_id] = true;
+					tour.push(node_id);
+					best = best.min(extend(tsp_data, tour, used, length + tsp_data.get_distance_between_via_id(last, node_id)));
+					tour.pop();
+					used[node_id] = false;
+				}
+			}
+
+			return best;
+		}
+
+		let mut used = vec![false; tsp_data.n];
+		used[0] = true;
+		return extend(tsp_data, &mut vec![0], &mut used, 0.0);
+	}
+
+	#[test]
+	fn
+	one_tree_is_lower_bound
+	()
+	{
+		let mut random_generator: StdRng = rand_seeder::Seeder::from(42).make_rng();
+
+		for _ in 0..30
+		{
+			let n = random_generator.gen_range(3..=8);
+			let tsp_data = random_instance(n, &mut random_generator);
+			let bb_data = bb_data(&tsp_data);
+			let optimum = optimal_tour_length(&tsp_data);
+
+			// The 1-tree is a lower bound for any penalties, and the optimized
+			// penalties do not lead to a worse bound than no penalties
+			let (unpenalized_bound, degrees) = bb_data.minimum_one_tree(&vec![0.0; n], &None);
+			assert_eq!(degrees.iter().sum::<usize>(), 2 * n);
+			assert!(unpenalized_bound <= optimum + 1e-6);
+
+			let penalties = bb_data.subgradient_optimization(&None);
+			let (bound, _) = bb_data.minimum_one_tree(&penalties, &None);
+			assert!(bound <= optimum + 1e-6);
+			assert!(bound >= unpenalized_bound - 1e-6);
+
+			let random_penalties = (0..n).map(|_| random_generator.gen_range(-20.0..20.0)).collect::<Vec<TSPWeight>>();
+			assert!(bb_data.minimum_one_tree(&random_penalties, &None).0 <= optimum + 1e-6);
+		}
+	}
+
+	#[test]
+	fn
+	sparse_spanning_tree_matches_dense
+	()
+	{
+		let mut random_generator: StdRng = rand_seeder::Seeder::from(7).make_rng();
+
+		for _ in 0..30
+		{
+			let n = random_generator.gen_range(2..=40);
+			let tsp_data = random_instance(n, &mut random_generator);
+			let bb_data = bb_data(&tsp_data);
+
+			let penalties = (0..n).map(|_| random_generator.gen_range(-20.0..20.0)).collect::<Vec<TSPWeight>>();
+			let complete = Some((0..n).map(|node_id| (0..n).filter(|other_id| *other_id != node_id).collect()).collect());
+			let sparse_tree = bb_data.minimum_spanning_tree(&penalties, &complete).unwrap();
+			let dense_tree = bb_data.minimum_spanning_tree(&penalties, &None).unwrap();
+
+			assert!((sparse_tree.length - dense_tree.length).abs() < 1e-6);
+			for tree in [sparse_tree, dense_tree]
+			{
+				assert_eq!(tree.order.len(), n);
+				assert_eq!(tree.parent.iter().filter(|parent| **parent == NO_PARENT).count(), 1);
+			}
+		}
+
+		// Edges that do not connect all nodes do not give a spanning tree
+		let tsp_data = random_instance(4, &mut random_generator);
+		let disconnected = Some(vec![vec![1], vec![0], vec![3], vec![2]]);
+		assert!(bb_data(&tsp_data).minimum_spanning_tree(&[0.0; 4], &disconnected).is_none());
+	}
+
+	#[test]
+	fn
+	alpha_candidates_match_brute_force
+	()
+	{
+		let mut random_generator: StdRng = rand_seeder::Seeder::from(3).make_rng();
+
+		for _ in 0..30
+		{
+			let n = random_generator.gen_range(3..=12);
+			let tsp_data = random_instance(n, &mut random_generator);
+			let bb_data = bb_data(&tsp_data);
+			let candidates = bb_data.alpha_nearness_edges();
+
+			// Recompute the penalties and the tree the same way
+			let adjacency = bb_data.one_tree_adjacency();
+			let penalties = bb_data.subgradient_optimization(&adjacency);
+			let tree = bb_data.minimum_spanning_tree(&penalties, &adjacency).unwrap();
+			let cost = |a: TSPNodeID, b: TSPNodeID| tsp_data.get_distance_between_via_id(a, b) + penalties[a] + penalties[b];
+
+			// The path from a node to the root of the tree
+			let path = |node_id: TSPNodeID|
+			{
+				let mut path = vec![node_id];
+				while tree.parent[*path.last().unwrap()] != NO_PARENT
+				{
+					path.push(tree.parent[*path.last().unwrap()]);
+				}
+				return path;
+			};
+
+			for a in 0..n
+			{
+				// The alpha value is the cost of the edge minus the largest
+				// cost on the tree path between its end nodes
+				let mut alphas = vec![(0.0, 0.0); n];
+				for b in (0..n).filter(|b| *b != a)
+				{
+					let (path_a, path_b) = (path(a), path(b));
+					let common = path_a.iter().find(|node_id| path_b.contains(node_id)).unwrap();
+					let beta = path_a.iter().take_while(|node_id| *node_id != common)
+						.chain(path_b.iter().take_while(|node_id| *node_id != common))
+						.map(|node_id| cost(*node_id, tree.parent[*node_id]))
+						.fold(TSPWeight::MIN, TSPWeight::max);
+
+					alphas[b] = (cost(a, b) - beta, cost(a, b));
+					if tree.parent[a] == b || tree.parent[b] == a
+					{
+						assert!(alphas[b].0.abs() < 1e-6);
+					}
+				}
+
+				// The candidates are the edges with the smallest alpha values
+				let mut expected = (0..n).filter(|b| *b != a).map(|b| alphas[b]).collect::<Vec<(TSPWeight, TSPWeight)>>();
+				expected.sort_by(|x, y| x.partial_cmp(y).unwrap());
+				assert_eq!(candidates[&a].len(), std::cmp::min(5, n - 1));
+				for (b, expected) in candidates[&a].iter().zip(expected.iter())
+				{
+					assert!((alphas[*b].0 - expected.0).abs() < 1e-6);
+				}
+			}
+		}
+	}
+}
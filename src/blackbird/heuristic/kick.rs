@@ -1,42 +1,165 @@
+use std::collections::HashSet;
+use std::collections::VecDeque;
+use std::str::FromStr;
+
 use rand::Rng;
+use rand::seq::SliceRandom;
 
 use crate::blackbird::data::bb_data::BBData;
+use crate::blackbird::data::bb_search::BBSearch;
+use crate::blackbird::data::bb_edge::BBEdge;
+use crate::blackbird::data::flipper::Flipper;
 use crate::tsp_lib::node::TSPNodeID;
 
 /// This enum describes which kicking strategy to use when CLK is applied
-/// Right now only "WALK" is implemented but CONCORDE provides some others as
-/// well that were not implemented at this point.
+/// The first four perform a double-bridge move and only differ in how the four
+/// edges to remove are chosen, just like the kicks provided by CONCORDE. The
+/// others are different kinds of perturbation for when CLK stagnates.
+#[allow(non_camel_case_types)]
+#[derive(Clone, Debug, PartialEq)]
 pub enum
 EKickType
 {
+	/// Random walks along the good edges, starting at a long tour edge
 	WALK,
+
+	/// Four edges chosen uniformly at random
+	RANDOM,
+
+	/// Edges starting at nodes that are geometrically near to a long edge
+	GEOMETRIC,
+
+	/// Edges starting at nodes that are reachable via few good edges from a
+	/// long tour edge
+	CLOSE,
+
+	/// Reversal of a random segment of the tour
+	SEGMENT_REVERSAL,
+
+	/// Moving a random segment of the tour next to a neighbor of its start
+	SEGMENT_INSERTION,
+
+	/// Restart from a new random tour
+	RANDOM_RESTART,
 }
 
 impl
-BBData
+FromStr
+for
+EKickType
+{
+	type Err = ();
+
+	/// Allows the creation of kick type enum values from given strings as
+	/// provided for example by the command line interface
+	fn
+	from_str
+	(
+		s: &str
+	)
+	-> Result<Self, Self::Err>
+	{
+		match s.to_lowercase().as_str().chars().nth(0).unwrap_or(' ')
+		{
+			'w'                        => Ok(EKickType::WALK),
+			'r'                        => Ok(EKickType::RANDOM),
+			'g'                        => Ok(EKickType::GEOMETRIC),
+			'c'                        => Ok(EKickType::CLOSE),
+			's'                        => Ok(EKickType::SEGMENT_REVERSAL),
+			'i'                        => Ok(EKickType::SEGMENT_INSERTION),
+			'n'                        => Ok(EKickType::RANDOM_RESTART),
+			_                          => Err(())
+		}
+	}
+}
+
+impl
+EKickType
+{
+	/// The kick type that gets used instead of this one while some edges are
+	/// fixed, as only double-bridge kicks can be checked to keep these edges
+	pub fn
+	with_fixed_edges
+	(
+		&self
+	)
+	-> EKickType
+	{
+		match self
+		{
+			EKickType::SEGMENT_REVERSAL
+			| EKickType::SEGMENT_INSERTION
+			| EKickType::RANDOM_RESTART => EKickType::WALK,
+			kick_type                   => kick_type.clone(),
+		}
+	}
+}
+
+impl<F: Flipper>
+BBSearch<'_, F>
 {
 	/// The main function for performing a kick to the tour currently stored in 
-	/// the flipper of the BBData struct
+	/// the flipper of the BBSearch struct
 	pub fn
 	kick
 	(
 		&mut self
 	)
 	{
-		let kick_type = EKickType::WALK;
-		let (
-			t1, t2, 
-			mut t3, mut t4, 
-			mut t5, mut t6, 
-			mut t7, mut t8
-		) = match kick_type
+		// With fixed edges only double-bridge kicks get used, as these can be
+		// checked to keep the fixed edges in the tour
+		let kick_type = if self.data.fixed_edges.is_empty() { self.data.config.kick_type.clone() }
+			else { self.data.config.kick_type.with_fixed_edges() };
+
+		for _ in 0..Self::FIXED_EDGE_TRIES
 		{
-			EKickType::WALK   => {
-				self.walk_kick()
-			},
-			_ => todo!(),
-		};
+			let double_bridge = match kick_type
+			{
+				EKickType::WALK              => self.walk_kick(),
+				EKickType::RANDOM            => self.random_kick(),
+				EKickType::GEOMETRIC         => self.geometric_kick(),
+				EKickType::CLOSE             => self.close_kick(),
+				EKickType::SEGMENT_REVERSAL  => return self.segment_reversal_kick(),
+				EKickType::SEGMENT_INSERTION => return self.segment_insertion_kick(),
+				EKickType::RANDOM_RESTART    => return self.random_restart_kick(),
+			};
+
+			if !self.removes_fixed_edge(double_bridge)
+			{
+				return self.double_bridge_kick(double_bridge);
+			}
+		}
+
+		self.dropped_kick_count += 1;
+	}
+
+	/// How often a kick gets chosen anew if it would remove a fixed edge,
+	/// before the tour is left as it is and the kick counts as dropped
+	const FIXED_EDGE_TRIES: usize = 100;
+
+	/// Checks if any of the four edges removed by a double-bridge move is a
+	/// fixed edge
+	fn
+	removes_fixed_edge
+	(
+		&self,
+		(t1, t2, t3, t4, t5, t6, t7, t8): (TSPNodeID, TSPNodeID, TSPNodeID, TSPNodeID, TSPNodeID, TSPNodeID, TSPNodeID, TSPNodeID),
+	)
+	-> bool
+	{
+		return [(t1, t2), (t3, t4), (t5, t6), (t7, t8)].iter()
+			.any(|&(x, y)| self.data.is_edge_fixed(&BBEdge::new_weightless(x, y)));
+	}
 
+	/// Performs a double-bridge move that removes the four edges (t1, t2), 
+	/// (t3, t4), (t5, t6) and (t7, t8)
+	fn
+	double_bridge_kick
+	(
+		&mut self,
+		(t1, t2, mut t3, mut t4, mut t5, mut t6, mut t7, mut t8): (TSPNodeID, TSPNodeID, TSPNodeID, TSPNodeID, TSPNodeID, TSPNodeID, TSPNodeID, TSPNodeID),
+	)
+	{
 		if !self.flipper.as_ref().unwrap().sequence(&t1, &t3, &t5)
 		{
 			(t3, t5) = (t5, t3);
@@ -71,25 +194,20 @@ BBData
 		// Updates the tour length caused by this kick
 		// Not sure if this is really correct
 		self.current_tour_length +=
-			- self.tsp_data.get_distance_between_via_id(t1, t6)
-			- self.tsp_data.get_distance_between_via_id(t2, t5)
-			- self.tsp_data.get_distance_between_via_id(t3, t8)
-			- self.tsp_data.get_distance_between_via_id(t4, t7)
-			+ self.tsp_data.get_distance_between_via_id(t1, t2)
-			+ self.tsp_data.get_distance_between_via_id(t3, t4)
-			+ self.tsp_data.get_distance_between_via_id(t5, t6)
-			+ self.tsp_data.get_distance_between_via_id(t7, t8);
+			- self.data.tsp_data.get_distance_between_via_id(t1, t6)
+			- self.data.tsp_data.get_distance_between_via_id(t2, t5)
+			- self.data.tsp_data.get_distance_between_via_id(t3, t8)
+			- self.data.tsp_data.get_distance_between_via_id(t4, t7)
+			+ self.data.tsp_data.get_distance_between_via_id(t1, t2)
+			+ self.data.tsp_data.get_distance_between_via_id(t3, t4)
+			+ self.data.tsp_data.get_distance_between_via_id(t5, t6)
+			+ self.data.tsp_data.get_distance_between_via_id(t7, t8);
 	}
 
-	/// Needed for the function that adds nodes (and their neighbors) that
-	/// participated in a kick to the queue of nodes to be processed in the 
-	/// next LK run
-	const MARK_LEVEL: usize = 10;
-
 	/// Adds the given node and its neighbors in one direcion (either towards
 	/// next or prev) to the queue of nodes that require processing in the next
 	/// LK run. The level of how deep this marking regarding neighbors goes is
-	/// controlled by [MARK_LEVEL]
+	/// controlled by the mark_level of the LK parameters
 	fn
 	add_node_and_neighbors_to_queue
 	(
@@ -101,22 +219,21 @@ BBData
 		self.node_queue.push_back(tx);
 
 		let mut k = tx;
-		for _ in 0..Self::MARK_LEVEL
+		for _ in 0..self.data.config.lk_params.mark_level
 		{
 			k = if (to_next) { self.flipper.as_ref().unwrap().next(&k) } else { self.flipper.as_ref().unwrap().prev(&k) };
 			self.node_queue.push_back(k)
 		}
 
-		for other_node_id in &self.sparse_edge_map[&tx]
+		for other_node_id in &self.data.sparse_edge_map[&tx]
 		{
 			self.node_queue.push_back(*other_node_id);
 		}
 	}
 
-	const WALK_STEPS: usize = 50;
-
 	/// The walk kick, the default kick method employed by Concorde. The code 
 	/// of this function is based heavily on the logic of the Concorde version. 
+	/// Falls back to the random kick if the walks don't find disjoint edges
 	fn
 	walk_kick
 	(
@@ -125,80 +242,373 @@ BBData
 	-> (TSPNodeID, TSPNodeID, TSPNodeID, TSPNodeID, TSPNodeID, TSPNodeID, TSPNodeID, TSPNodeID)
 	{
 		let (s1, s2) = self.first_kicker();
-		let mut s3: TSPNodeID;
-		let mut s4: TSPNodeID;
-		let mut s5: TSPNodeID;
-		let mut s6: TSPNodeID;
-		let mut s7: TSPNodeID;
-		let mut s8: TSPNodeID;
 
-		loop
+		for _ in 0..Self::WALK_KICK_TRIES
 		{
 			let mut old = TSPNodeID::MAX;
 			let mut n   = s2;
 
-			for _ in 0..Self::WALK_STEPS
+			for _ in 0..self.data.config.lk_params.walk_steps
 			{
-				let j = self.random_generator.gen_range(0..self.sparse_edge_map[&n].len());
+				let j = self.random_generator.gen_range(0..self.data.sparse_edge_map[&n].len());
 
-				if old != self.sparse_edge_map[&n][j]
+				if old != self.data.sparse_edge_map[&n][j]
 				{
 					old = n;
-					n = self.sparse_edge_map[&n][j]
+					n = self.data.sparse_edge_map[&n][j]
 				}
 			}
 
-			s3 = n;
-			s4 = self.flipper.as_ref().unwrap().next(&s3);
+			let s3 = n;
+			let s4 = self.flipper.as_ref().unwrap().next(&s3);
 			n  = s4;
 
-			for _ in 0..Self::WALK_STEPS
+			for _ in 0..self.data.config.lk_params.walk_steps
 			{
-				let j = self.random_generator.gen_range(0..self.sparse_edge_map[&n].len());
+				let j = self.random_generator.gen_range(0..self.data.sparse_edge_map[&n].len());
 
-				if old != self.sparse_edge_map[&n][j]
+				if old != self.data.sparse_edge_map[&n][j]
 				{
 					old = n;
-					n = self.sparse_edge_map[&n][j]
+					n = self.data.sparse_edge_map[&n][j]
 				}
 			}
 
-			s5 = n;
-			s6 = self.flipper.as_ref().unwrap().next(&s5);
+			let s5 = n;
+			let s6 = self.flipper.as_ref().unwrap().next(&s5);
 			n  = s6;
 
-			for _ in 0..Self::WALK_STEPS
+			for _ in 0..self.data.config.lk_params.walk_steps
 			{
-				let j = self.random_generator.gen_range(0..self.sparse_edge_map[&n].len());
+				let j = self.random_generator.gen_range(0..self.data.sparse_edge_map[&n].len());
 
-				if old != self.sparse_edge_map[&n][j]
+				if old != self.data.sparse_edge_map[&n][j]
 				{
 					old = n;
-					n = self.sparse_edge_map[&n][j]
+					n = self.data.sparse_edge_map[&n][j]
 				}
 			}
 
-			s7 = n;
-			s8 = self.flipper.as_ref().unwrap().next(&s7);
+			let s7 = n;
+			let s8 = self.flipper.as_ref().unwrap().next(&s7);
+
+			if Self::is_valid_kick(s1, s2, s3, s4, s5, s6, s7, s8)
+			{
+				return (s1, s2, s3, s4, s5, s6, s7, s8);
+			}
+		}
+
+		// The walks keep ending up at the same few nodes, e.g. if the good
+		// edges around the long edge only reach a handful of nodes
+		return self.random_kick();
+	}
+
+	/// How often the random walks of the walk kick get repeated to find four
+	/// disjoint edges, before falling back to the random kick
+	const WALK_KICK_TRIES: usize = 100;
 
-			if 
-			!(
-				   s1 == s3 || s1 == s4 || s1 == s5 || s1 == s6 || s1 == s7 || s1 == s8
-				|| s2 == s3 || s2 == s4 || s2 == s5 || s2 == s6 || s2 == s7 || s2 == s8
+	/// Checks that the four edges (s1, s2), (s3, s4), (s5, s6) and (s7, s8) 
+	/// chosen for a kick are pairwise disjoint
+	#[allow(clippy::too_many_arguments)]
+	fn
+	is_valid_kick
+	(
+		s1:                            TSPNodeID,
+		s2:                            TSPNodeID,
+		s3:                            TSPNodeID,
+		s4:                            TSPNodeID,
+		s5:                            TSPNodeID,
+		s6:                            TSPNodeID,
+		s7:                            TSPNodeID,
+		s8:                            TSPNodeID,
+	)
+	-> bool
+	{
+		return !(
+			   s1 == s3 || s1 == s4 || s1 == s5 || s1 == s6 || s1 == s7 || s1 == s8
+			|| s2 == s3 || s2 == s4 || s2 == s5 || s2 == s6 || s2 == s7 || s2 == s8
 										|| s3 == s5 || s3 == s6 || s3 == s7 || s3 == s8
 										|| s4 == s5 || s4 == s6 || s4 == s7 || s4 == s8
-																|| s5 == s7 || s5 == s8
-																|| s6 == s7 || s6 == s8
-			)
+														|| s5 == s7 || s5 == s8
+														|| s6 == s7 || s6 == s8
+		);
+	}
+
+	/// The random kick, which removes four edges that are chosen uniformly at
+	/// random from the tour
+	fn
+	random_kick
+	(
+		&mut self
+	)
+	-> (TSPNodeID, TSPNodeID, TSPNodeID, TSPNodeID, TSPNodeID, TSPNodeID, TSPNodeID, TSPNodeID)
+	{
+		loop
+		{
+			let s1 = self.random_generator.gen_range(0..self.data.tsp_data.n);
+			let s3 = self.random_generator.gen_range(0..self.data.tsp_data.n);
+			let s5 = self.random_generator.gen_range(0..self.data.tsp_data.n);
+			let s7 = self.random_generator.gen_range(0..self.data.tsp_data.n);
+
+			let s2 = self.flipper.as_ref().unwrap().next(&s1);
+			let s4 = self.flipper.as_ref().unwrap().next(&s3);
+			let s6 = self.flipper.as_ref().unwrap().next(&s5);
+			let s8 = self.flipper.as_ref().unwrap().next(&s7);
+
+			if Self::is_valid_kick(s1, s2, s3, s4, s5, s6, s7, s8)
+			{
+				return (s1, s2, s3, s4, s5, s6, s7, s8);
+			}
+		}
+	}
+
+	/// The number of nodes near the starting point of the geometric and the
+	/// close kick from which the other three edges get chosen
+	const NEARBY_COUNT: usize = 50;
+
+	/// How often the geometric and the close kick try to find three edges near
+	/// the starting point before falling back to the random kick
+	const NEARBY_TRIES: usize = 100;
+
+	/// The geometric kick, which removes a long edge and three edges starting
+	/// at nodes that are near to it according to the KD tree
+	fn
+	geometric_kick
+	(
+		&mut self
+	)
+	-> (TSPNodeID, TSPNodeID, TSPNodeID, TSPNodeID, TSPNodeID, TSPNodeID, TSPNodeID, TSPNodeID)
+	{
+		let (s1, s2) = self.first_kicker();
+
+		let nearby_nodes = self.data.kd_tree.as_ref().unwrap().nearests(
+			&self.data.tsp_data.nodes[s1], 
+			Self::NEARBY_COUNT, 
+			self.data.tsp_data
+		).iter().map(|&(node, _)| node.id).filter(|node_id| node_id != &s1).collect::<Vec<TSPNodeID>>();
+
+		return self.nearby_kick(s1, s2, &nearby_nodes);
+	}
+
+	/// The close kick, which removes a long edge and three edges starting at
+	/// nodes that are reachable from it via the fewest number of good edges
+	fn
+	close_kick
+	(
+		&mut self
+	)
+	-> (TSPNodeID, TSPNodeID, TSPNodeID, TSPNodeID, TSPNodeID, TSPNodeID, TSPNodeID, TSPNodeID)
+	{
+		let (s1, s2) = self.first_kicker();
+
+		// Breadth-first search along the good edges
+		let mut nearby_nodes = Vec::with_capacity(Self::NEARBY_COUNT);
+		let mut visited = HashSet::from([s1]);
+		let mut queue = VecDeque::from([s1]);
+
+		while let Some(node_id) = queue.pop_front()
+		{
+			if nearby_nodes.len() >= Self::NEARBY_COUNT
 			{
 				break;
 			}
+
+			for other_node_id in &self.data.sparse_edge_map[&node_id]
+			{
+				if visited.insert(*other_node_id)
+				{
+					nearby_nodes.push(*other_node_id);
+					queue.push_back(*other_node_id);
+				}
+			}
 		}
 
-		return (s1, s2, s3, s4, s5, s6, s7, s8);
+		return self.nearby_kick(s1, s2, &nearby_nodes);
 	}
 
+	/// Completes the edge (s1, s2) to a kick by choosing the other three edges
+	/// so that they start at the given nearby nodes
+	fn
+	nearby_kick
+	(
+		&mut self,
+		s1:                            TSPNodeID,
+		s2:                            TSPNodeID,
+		nearby_nodes:                  &[TSPNodeID],
+	)
+	-> (TSPNodeID, TSPNodeID, TSPNodeID, TSPNodeID, TSPNodeID, TSPNodeID, TSPNodeID, TSPNodeID)
+	{
+		if nearby_nodes.len() >= 3
+		{
+			for _ in 0..Self::NEARBY_TRIES
+			{
+				let s3 = nearby_nodes[self.random_generator.gen_range(0..nearby_nodes.len())];
+				let s5 = nearby_nodes[self.random_generator.gen_range(0..nearby_nodes.len())];
+				let s7 = nearby_nodes[self.random_generator.gen_range(0..nearby_nodes.len())];
+
+				let s4 = self.flipper.as_ref().unwrap().next(&s3);
+				let s6 = self.flipper.as_ref().unwrap().next(&s5);
+				let s8 = self.flipper.as_ref().unwrap().next(&s7);
+
+				if Self::is_valid_kick(s1, s2, s3, s4, s5, s6, s7, s8)
+				{
+					return (s1, s2, s3, s4, s5, s6, s7, s8);
+				}
+			}
+		}
 
+		// Not enough distinct edges near the starting point
+		return self.random_kick();
+	}
+
+	/// The maximum number of nodes of a segment that gets reversed or moved by
+	/// the segment kicks
+	const SEGMENT_LENGTH_MAX: usize = 50;
+
+	/// Chooses a random segment of the tour for the segment kicks and returns
+	/// its first and last node. The segment contains at least two nodes and
+	/// leaves at least three other nodes in the tour
+	fn
+	random_segment
+	(
+		&mut self
+	)
+	-> (TSPNodeID, TSPNodeID)
+	{
+		let length_max = std::cmp::min(Self::SEGMENT_LENGTH_MAX, self.data.tsp_data.n - 3);
+		let length = self.random_generator.gen_range(2..=length_max);
+
+		let first: TSPNodeID = self.random_generator.gen_range(0..self.data.tsp_data.n);
+		let mut last = first;
+		for _ in 1..length
+		{
+			last = self.flipper.as_ref().unwrap().next(&last);
+		}
+
+		return (first, last);
+	}
+
+	/// The segment reversal kick, which reverses a random segment of the tour
+	/// (i.e. performs a random 2-opt move)
+	fn
+	segment_reversal_kick
+	(
+		&mut self
+	)
+	{
+		let (first, last) = self.random_segment();
+		let prev = self.flipper.as_ref().unwrap().prev(&first);
+		let next = self.flipper.as_ref().unwrap().next(&last);
+
+		self.flipper.as_mut().unwrap().flip(first, last);
+
+		self.add_node_and_neighbors_to_queue(prev, false);
+		self.add_node_and_neighbors_to_queue(first, true);
+		self.add_node_and_neighbors_to_queue(last, false);
+		self.add_node_and_neighbors_to_queue(next, true);
+
+		self.current_tour_length +=
+			- self.data.tsp_data.get_distance_between_via_id(prev, first)
+			- self.data.tsp_data.get_distance_between_via_id(last, next)
+			+ self.data.tsp_data.get_distance_between_via_id(prev, last)
+			+ self.data.tsp_data.get_distance_between_via_id(first, next);
+	}
+
+	/// The segment insertion kick, which removes a random segment of the tour
+	/// and inserts it again next to one of the good neighbors of its first 
+	/// node, in whichever direction is shorter (i.e. performs an or-opt move)
+	fn
+	segment_insertion_kick
+	(
+		&mut self
+	)
+	{
+		let (first, last) = self.random_segment();
+		let prev = self.flipper.as_ref().unwrap().prev(&first);
+		let next = self.flipper.as_ref().unwrap().next(&last);
+
+		// The segment gets inserted between c and d, which must not be part
+		// of the segment or its previous node (which wouldn't move it at all)
+		let is_outside = |flipper: &F, node_id: &TSPNodeID| 
+			node_id != &prev && node_id != &first && !flipper.sequence(&first, node_id, &last);
+
+		let neighbors = self.data.sparse_edge_map[&first].clone();
+		let mut c = neighbors[self.random_generator.gen_range(0..neighbors.len())];
+		while !is_outside(self.flipper.as_ref().unwrap(), &c)
+		{
+			c = self.random_generator.gen_range(0..self.data.tsp_data.n);
+		}
+		let d = self.flipper.as_ref().unwrap().next(&c);
+
+		// Moving the segment is done via reversals: The tour 
+		// prev first..last next..c d 
+		// becomes 
+		// prev c..next last..first d 
+		// and then 
+		// prev next..c last..first d
+		self.flipper.as_mut().unwrap().flip(first, c);
+		if c != next
+		{
+			self.flipper.as_mut().unwrap().flip(c, next);
+		}
+
+		let removed_length =
+			  self.data.tsp_data.get_distance_between_via_id(prev, first)
+			+ self.data.tsp_data.get_distance_between_via_id(last, next)
+			+ self.data.tsp_data.get_distance_between_via_id(c, d);
+		let reversed_length = 
+			  self.data.tsp_data.get_distance_between_via_id(prev, next)
+			+ self.data.tsp_data.get_distance_between_via_id(c, last)
+			+ self.data.tsp_data.get_distance_between_via_id(first, d);
+		let forward_length = 
+			  self.data.tsp_data.get_distance_between_via_id(prev, next)
+			+ self.data.tsp_data.get_distance_between_via_id(c, first)
+			+ self.data.tsp_data.get_distance_between_via_id(last, d);
+
+		// Restore the original direction of the segment if that is shorter
+		if forward_length < reversed_length
+		{
+			self.flipper.as_mut().unwrap().flip(last, first);
+			self.current_tour_length += forward_length - removed_length;
+		}
+		else
+		{
+			self.current_tour_length += reversed_length - removed_length;
+		}
+
+		self.add_node_and_neighbors_to_queue(prev, false);
+		self.add_node_and_neighbors_to_queue(first, true);
+		self.add_node_and_neighbors_to_queue(last, false);
+		self.add_node_and_neighbors_to_queue(next, true);
+		self.add_node_and_neighbors_to_queue(c, false);
+		self.add_node_and_neighbors_to_queue(d, true);
+	}
+
+	/// The random restart kick, which replaces the current tour by a new 
+	/// random one. The best tour found so far is kept as output tour.
+	fn
+	random_restart_kick
+	(
+		&mut self
+	)
+	{
+		let random_tour = BBData::random_tour(self.data.tsp_data, &mut self.random_generator);
+
+		let random_tour_cycle = random_tour.is_valid(self.data.tsp_data.n, false).expect("Could not create a valid random tour!");
+		self.flipper = Some(F::new(&random_tour_cycle));
+		self.current_tour_length = random_tour.compute_len();
+
+		// All of the nodes need to be processed by the next LK run
+		let mut shuffled_node_ids = random_tour_cycle;
+		shuffled_node_ids.shuffle(&mut self.random_generator);
+		self.node_queue = VecDeque::from(shuffled_node_ids);
+
+		self.edge_markings.clear();
+		for edge in &random_tour.edges
+		{
+			self.mark_edge_as_added(edge);
+		}
+	}
 
 	/// Gives a starting point for the walk kick
 	fn
@@ -211,12 +621,12 @@ BBData
 		let mut t1;
 		let mut t2;
 
-		let mut try1: TSPNodeID = self.random_generator.gen_range(0..self.tsp_data.n);
+		let mut try1: TSPNodeID = self.random_generator.gen_range(0..self.data.tsp_data.n);
 		let mut next = self.flipper.as_ref().unwrap().next(&try1);
 		let mut prev = self.flipper.as_ref().unwrap().prev(&try1);
 
-		let mut edge_length_to_next = self.tsp_data.get_distance_between_via_id(try1, next);
-		let mut edge_length_to_prev = self.tsp_data.get_distance_between_via_id(try1, prev);
+		let mut edge_length_to_next = self.data.tsp_data.get_distance_between_via_id(try1, next);
+		let mut edge_length_to_prev = self.data.tsp_data.get_distance_between_via_id(try1, prev);
 
 		let best;
 
@@ -226,9 +636,9 @@ BBData
 			t2 = next;
 			best = 
 				edge_length_to_next 
-				- self.tsp_data.get_distance_between_via_id(
+				- self.data.tsp_data.get_distance_between_via_id(
 					t1, 
-					*self.sparse_edge_map[&t1].first().unwrap()
+					*self.data.sparse_edge_map[&t1].first().unwrap()
 				);
 		}
 		else
@@ -237,19 +647,19 @@ BBData
 			t2 = try1;
 			best = 
 				edge_length_to_prev 
-				- self.tsp_data.get_distance_between_via_id(
+				- self.data.tsp_data.get_distance_between_via_id(
 					t1, 
-					*self.sparse_edge_map[&t1].first().unwrap()
+					*self.data.sparse_edge_map[&t1].first().unwrap()
 				);
 		}
 
-		for _ in 0..((self.tsp_data.n as f64 * 0.001) as usize +10)
+		for _ in 0..((self.data.tsp_data.n as f64 * 0.001) as usize +10)
 		{
-			try1 = self.random_generator.gen_range(0..self.tsp_data.n);
+			try1 = self.random_generator.gen_range(0..self.data.tsp_data.n);
 			next = self.flipper.as_ref().unwrap().next(&try1);
 			prev = self.flipper.as_ref().unwrap().prev(&try1);
-			edge_length_to_next = self.tsp_data.get_distance_between_via_id(try1, next);
-			edge_length_to_prev = self.tsp_data.get_distance_between_via_id(try1, prev);
+			edge_length_to_next = self.data.tsp_data.get_distance_between_via_id(try1, next);
+			edge_length_to_prev = self.data.tsp_data.get_distance_between_via_id(try1, prev);
 
 			// Update t1 and t2 in case a better edge has been found
 			// Note that CONCORDE does NOT update the value of best at this 
@@ -258,9 +668,9 @@ BBData
 			{
 				let len = 
 					edge_length_to_next 
-					- self.tsp_data.get_distance_between_via_id(
+					- self.data.tsp_data.get_distance_between_via_id(
 						try1, 
-						*self.sparse_edge_map[&try1].first().unwrap()
+						*self.data.sparse_edge_map[&try1].first().unwrap()
 					);
 
 				if len > best
@@ -273,9 +683,9 @@ BBData
 			{
 				let len = 
 					edge_length_to_prev 
-					- self.tsp_data.get_distance_between_via_id(
+					- self.data.tsp_data.get_distance_between_via_id(
 						try1, 
-						*self.sparse_edge_map[&try1].first().unwrap()
+						*self.data.sparse_edge_map[&try1].first().unwrap()
 					);
 
 				if len > best
@@ -0,0 +1,126 @@
+use crate::blackbird::error::SolverError;
+
+/// The parameters that control the shape of the search performed by LK
+/// The default values are the ones used by Concorde
+#[derive(Clone, Debug)]
+pub struct
+LKParams
+{
+	/// The number of alternatives to try at each level of the search that
+	/// allows backtracking, see "backtrack_count" in "linkern.c"
+	/// Deeper levels only try a single alternative (up to max_depth)
+	pub breadth:                       Vec<usize>,
+
+	/// The number of levels of the search that allow backtracking
+	pub backtrack:                     usize,
+
+	/// The maximum depth of the search, i.e. the maximum number of flips
+	/// performed by a single step of LK
+	pub max_depth:                     usize,
+
+	/// How many tour neighbors of the nodes involved in a kick are added to
+	/// the queue of nodes to be processed in the next LK run
+	pub mark_level:                    usize,
+
+	/// The number of steps of each of the random walks of the walk kick
+	pub walk_steps:                    usize,
+
+	/// The number of alternatives to try at each of the three levels of the
+	/// alternate first step
+	pub alternate_look_ahead_max_1:    usize,
+	pub alternate_look_ahead_max_2:    usize,
+	pub alternate_look_ahead_max_3:    usize,
+}
+
+impl
+Default
+for
+LKParams
+{
+	fn
+	default
+	()
+	-> Self
+	{
+		// According to this source:
+		// "The Traveling Salesman Problem: A Computational Study" by
+		// - David L. Applegate
+		// - Robert E. Bixby
+		// - Vašek Chvatál
+		// - William J. Cook
+		// ISBN: 9780691129938
+		// Page 452
+		// The breadth is set to 0 for levels deeper than 25
+		// Furthermore, for the first values, see "backtrack_count" in "linkern.c"
+		LKParams
+		{
+			breadth:                       vec![4, 3, 3, 2],
+			backtrack:                     4,
+			max_depth:                     25,
+			mark_level:                    10,
+			walk_steps:                    50,
+			alternate_look_ahead_max_1:    4,
+			alternate_look_ahead_max_2:    3,
+			alternate_look_ahead_max_3:    3,
+		}
+	}
+}
+
+impl
+LKParams
+{
+	/// The number of alternatives to try at the given level of the search
+	pub fn
+	breadth
+	(
+		&self,
+		level:                         usize,
+	)
+	-> usize
+	{
+		if level >= self.max_depth
+		{
+			return 0;
+		}
+
+		return *self.breadth.get(level).unwrap_or(&1);
+	}
+
+	/// Checks that the parameters describe a search LK can perform, i.e. at
+	/// least one level with backtracking and at least one alternative at each
+	/// level that allows backtracking
+	pub fn
+	validate
+	(
+		&self
+	)
+	-> Result<(), SolverError>
+	{
+		let reason = if self.breadth.is_empty()
+		{
+			"The breadth needs at least one value".to_string()
+		}
+		else if self.breadth.contains(&0)
+		{
+			"The breadth needs to be at least 1 at every level".to_string()
+		}
+		else if self.backtrack == 0 || self.backtrack > self.breadth.len()
+		{
+			format!("The backtrack levels need to be between 1 and the {} values of the breadth but are {}", self.breadth.len(), self.backtrack)
+		}
+		else if self.max_depth < self.backtrack
+		{
+			format!("The maximum depth {} is less than the {} backtrack levels", self.max_depth, self.backtrack)
+		}
+		else if self.alternate_look_ahead_max_1 == 0 || self.alternate_look_ahead_max_2 == 0 || self.alternate_look_ahead_max_3 == 0
+		{
+			"The alternate breadth needs to be at least 1 at every level".to_string()
+		}
+		else
+		{
+			return Ok(());
+		};
+
+		return Err(SolverError::InvalidLKParams { reason: reason });
+	}
+}
@@ -1,57 +1,194 @@
 use std::collections::VecDeque;
+use std::sync::Mutex;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
 use std::time::Instant;
 
+use rand::Rng;
 use rand::seq::SliceRandom;
 
 use crate::blackbird::data::bb_data::*;
-use crate::blackbird::data::bb_flipper::BBFlipper;
+use crate::blackbird::data::bb_search::BBSearch;
+use crate::blackbird::data::flipper::Flipper;
 use crate::blackbird::data::bb_edge::BBEdge;
+use crate::blackbird::error::SolverError;
+use crate::blackbird::solver::SolverResult;
 use crate::tsp_lib::node::TSPNodeID;
 use crate::tsp_lib::node::TSPWeight;
+use crate::warn;
+
+/// The outcome of a single trial of CLK
+struct
+BBTrialResult
+{
+	index:                             usize,
+	tour:                              Vec<TSPNodeID>,
+	length:                            TSPWeight,
+	kick_count:                        usize,
+	improving_kick_count:              usize,
+}
 
 impl
-BBData
+BBData<'_>
 {
 	/// The main function for handling the call to the heuristic
 	/// This performs
 	/// - Time measurement
 	/// - Initial tour creation
 	/// - Computing of the sparse edge map
-	/// - Performing one or multiple runs of CLK
-	/// - Printing the final results
+	/// - Performing one or multiple trials of CLK
+	/// - Returning the final results
 	pub fn
-	main_heuristic
+	main_heuristic<F: Flipper>
 	(
 		&mut self
 	)
+	-> Result<SolverResult, SolverError>
 	{
 		// Start time measurement
-		let time_measurement_start = Instant::now();
+		let time_measurement_start = self.solve_start;
 
-		// Create an initial tour with the selected algorithm
-		self.create_initial_tour();
+		// A tour needs at least two nodes
+		if self.tsp_data.n < 2
+		{
+			return Err(SolverError::TooFewNodes { n: self.tsp_data.n });
+		}
 
-		// Compute the sparse set of good edges to consider during CLK
-		self.sparse_edge_map();
+		// Create an initial tour with the selected algorithm first, so that
+		// an invalid tour (e.g. given by file) gets reported right away
+		self.create_initial_tour()?;
 
-		// Apply CLK as often as specified (or just once)
-		for _ in 0..(if (self.cli_args.number_of_runs > 0) { self.cli_args.number_of_runs } else { 1 })
+		// Compute the sparse set of good edges to consider during CLK, unless
+		// this already happened for the greedy initial tour or there is no
+		// time left for CLK anyway
+		if self.sparse_edge_map.is_empty() && !self.time_limit_reached()
 		{
-			// Call the CLK heuristic
-			self.chained_lin_kernighan();
+			self.sparse_edge_map();
 		}
 
+		// Apply CLK as often as specified (or just once), where each trial
+		// gets its own seed that is derived from the main random generator
+		// If the time limit has already been used up by the steps above, CLK
+		// is skipped and the initial tour is the result
+		let number_of_trials = if self.config.number_of_runs > 0 { self.config.number_of_runs as usize } else { 1 };
+		let seeds = (0..number_of_trials).map(|_| self.random_generator.gen::<u64>()).collect::<Vec<u64>>();
+		let merge_seed = self.random_generator.gen::<u64>();
+		let trials = if self.time_limit_reached()
+		{
+			warn!("The time limit has been reached before applying CLK, returning the initial tour!");
+			Vec::new()
+		}
+		else
+		{
+			self.run_trials::<F>(&seeds)
+		};
+
 		// Total runtime
-		if (self.cli_args.verbose)
+		if (self.config.verbose)
 		{
 			println!("Total runtime : {}µs", time_measurement_start.elapsed().as_micros());
 		}
 
+		// Keep the best tour of all trials (or the initial tour without any)
+		let (best_tour, best_length) = match trials.iter().min_by(|a, b| a.length.total_cmp(&b.length))
+		{
+			Some(best) => (best.tour.clone(), best.length),
+			None       => (self.initial_tour_cycle.clone().unwrap(), self.initial_tour_length),
+		};
+
+		let kick_count = trials.iter().map(|trial| trial.kick_count).sum();
+		let improving_kick_count = trials.iter().map(|trial| trial.improving_kick_count).sum();
+
+		// Merge the tours of the trials, if there is more than one of them
+		let (tour, length, dropped_kick_count) = if self.config.tour_merging && trials.len() > 1 && !self.time_limit_reached()
+		{
+			let tours = trials.iter().map(|trial| &trial.tour).collect::<Vec<&Vec<TSPNodeID>>>();
+			let (merged_tour, merged_length, dropped_kick_count) = self.merge_tours::<F>(&tours, &best_tour, merge_seed);
+			if (self.config.verbose)
+			{
+				println!("Merged tour length : {}", merged_length);
+			}
+			(merged_tour, merged_length, dropped_kick_count)
+		}
+		else
+		{
+			(best_tour, best_length, 0)
+		};
+
 		// The final result
-		println!("Final tour length : {}", self.output_tour_length);
-		println!("Total runtime : {}", (time_measurement_start.elapsed().as_micros() as f64) / 1000000.0);
+		return Ok(SolverResult
+		{
+			external_tour:             tour.iter().map(|node_id| self.tsp_data.external_id(*node_id)).collect(),
+			tour:                      tour,
+			initial_tour_length:       self.initial_tour_length,
+			length:                    length,
+			runtime:                   time_measurement_start.elapsed(),
+			kick_count:                kick_count,
+			improving_kick_count:      improving_kick_count,
+			dropped_kick_count:        dropped_kick_count,
+			trial_lengths:             trials.iter().map(|trial| trial.length).collect(),
+		});
+	}
+
+	/// Runs one trial of CLK for each of the given seeds on a pool of threads
+	/// Each trial has its own flipper and markings and only reads the shared
+	/// data. Once the time limit is reached, no further trials get started
+	/// (apart from the first one). The results are ordered by trial
+	fn
+	run_trials<F: Flipper>
+	(
+		&self,
+		seeds:                         &[u64],
+	)
+	-> Vec<BBTrialResult>
+	{
+		let number_of_threads = if self.config.number_of_threads > 0 { self.config.number_of_threads }
+			else { std::thread::available_parallelism().map(|count| count.get()).unwrap_or(1) };
+
+		let next_trial = AtomicUsize::new(0);
+		let results = Mutex::new(Vec::new());
+
+		std::thread::scope(|scope|
+		{
+			for _ in 0..std::cmp::min(number_of_threads, seeds.len())
+			{
+				scope.spawn(||
+				{
+					loop
+					{
+						let index = next_trial.fetch_add(1, Ordering::Relaxed);
+						if index >= seeds.len() || (index > 0 && self.time_limit_reached())
+						{
+							break;
+						}
+
+						// The flipper is created by the trial itself, so it
+						// never has to be shared between threads
+						let mut search = BBSearch::<F>::new(self, seeds[index]);
+						search.chained_lin_kernighan();
+
+						results.lock().unwrap().push(BBTrialResult
+						{
+							index:                 index,
+							tour:                  search.output_tour_cycle.take().unwrap(),
+							length:                search.output_tour_length,
+							kick_count:            search.kick_count,
+							improving_kick_count:  search.improving_kick_count,
+						});
+					}
+				});
+			}
+		});
+
+		let mut results = results.into_inner().unwrap();
+		results.sort_by_key(|result| result.index);
+		return results;
 	}
+}
 
+impl<F: Flipper>
+BBSearch<'_, F>
+{
 	/// The heart of the heuristic which chains multiple calls to LK and the
 	/// kick method together to form CLK. This performs
 	/// - Time measurement
@@ -59,7 +196,7 @@ BBData
 	/// - Creation of the queue for the nodes to be processed
 	/// - A first call to LK
 	/// - A loop of kicking and calling LK
-	fn
+	pub fn
 	chained_lin_kernighan
 	(
 		&mut self
@@ -69,58 +206,70 @@ BBData
 		let time_measurement_start = Instant::now();
 
 		// Construct the flipper for storing the current tour and handling flips
-		self.flipper = Some(BBFlipper::new(&self.initial_tour_cycle.as_ref().unwrap()));
+		self.flipper = Some(F::new(self.data.initial_tour_cycle.as_ref().unwrap()));
 
 		// Initialize the node queue with random order
-		let mut shuffled_node_ids = self.tsp_data.nodes.iter().map(|node| node.id).collect::<Vec<TSPNodeID>>();
+		let mut shuffled_node_ids = self.data.tsp_data.nodes.iter().map(|node| node.id).collect::<Vec<TSPNodeID>>();
 		shuffled_node_ids.shuffle(&mut self.random_generator);
 		self.node_queue = VecDeque::from(shuffled_node_ids);
 
 		// Clear the edge markings
 		self.edge_markings.clear();
 
-		for edge in &self.current_tour.as_ref().unwrap().edges.clone()
+		for edge in &self.data.current_tour.as_ref().unwrap().edges.clone()
 		{
-			self.mark_edge_as_added(&edge);
+			self.mark_edge_as_added(edge);
 		}
 
 		// Call Lin Kernighan for the first time before we can kick the tour
 		self.lin_kernighan();
 
-		let number_of_kicks = self.tsp_data.n;
-		let stall_count = 10000000;
+		// A kick requires at least 8 distinct nodes, so smaller instances are
+		// only improved by the first call to LK
+		// Just like for Concorde, the default number of kicks is the number of
+		// nodes (if the number of kicks is not given)
+		let number_of_kicks = if self.data.tsp_data.n < 8 { 0 }
+			else if self.data.config.number_of_kicks > 0 { self.data.config.number_of_kicks } 
+			else { self.data.tsp_data.n };
+		let stall_count = self.data.config.stall_count;
 
 		let mut quitcount = std::cmp::min(stall_count, number_of_kicks);
 		let mut round = 0;
 
 		while round < quitcount
 		{
-			self.current_tour_length = self.flipper.as_ref().unwrap().cost(&self.tsp_data);
+			self.current_tour_length = self.flipper.as_ref().unwrap().cost(self.data.tsp_data);
 	
 			self.kick();
+			self.kick_count += 1;
 	
+			// Stop after stall_count kicks without any improvement
 			if self.lin_kernighan()
 			{
+				self.improving_kick_count += 1;
 				quitcount = std::cmp::min(round + stall_count, number_of_kicks);
 			}
 
 			// Stop if time bound is reached
-			if (self.cli_args.time_limit <= time_measurement_start.elapsed().as_secs())
+			if self.time_limit_reached()
 			{
 				break;
 			}
 
 			// Stop if length bound is reached
-			if (self.cli_args.length_limit >= self.output_tour_length)
+			if (self.data.config.length_limit >= self.output_tour_length)
 			{
 				break;
 			}
 
 			// Increase round counter
-			round += quitcount;
+			round += 1;
 		}
 
-		println!("CLK runtime : {}", (time_measurement_start.elapsed().as_micros() as f64) / 1000000.0);
+		if (self.data.config.verbose)
+		{
+			println!("CLK runtime : {}", (time_measurement_start.elapsed().as_micros() as f64) / 1000000.0);
+		}
 	}
 
 
@@ -135,6 +284,13 @@ BBData
 	{
 		while let Some(start) = self.node_queue.pop_front()
 		{
+			// Stop if time bound is reached, which leaves a valid tour as each
+			// improvement is either completed or undone
+			if self.time_limit_reached()
+			{
+				break;
+			}
+
 			self.improve(start);
 		}
 
@@ -142,7 +298,7 @@ BBData
 		let mut length = 0.0;
 		for i in 0..output_tour_cycle.len()
 		{
-			length += self.tsp_data.get_distance_between_via_id(output_tour_cycle[i], output_tour_cycle[(i+1)%self.tsp_data.n]);
+			length += self.data.tsp_data.get_distance_between_via_id(output_tour_cycle[i], output_tour_cycle[(i+1)%self.data.tsp_data.n]);
 		}
 
 		if length < self.output_tour_length
@@ -166,9 +322,15 @@ BBData
 	-> TSPWeight
 	{
 		let base_next = self.flipper.as_ref().unwrap().next(&base);
-		let edge = BBEdge::new(&self.tsp_data, base, base_next);
+		let edge = BBEdge::new(self.data.tsp_data, base, base_next);
 		let gain = edge.weight;
 
+		// A fixed edge must remain part of the tour
+		if self.data.is_edge_fixed(&edge)
+		{
+			return 0.0;
+		}
+
 		self.mark_edge_as_deleted(&edge);
 
 		let (hit, mut g_star) = self.step(base, base_next, 0, gain, 0.0);
@@ -189,9 +351,6 @@ BBData
 		return g_star;
 	}
 
-	const BACKTRACK: usize = 4;
-	const MAX_DEPTH: usize = 25;
-
 	// Implements the step function as described in 
 	// "The Traveling Salesman Problem: A Computational Study" by
 	// - David L. Applegate
@@ -216,7 +375,7 @@ BBData
 		let mut hits = 0;
 		let mut g_star = old_g_star;
 
-		if (level >= Self::BACKTRACK)
+		if (level >= self.data.config.lk_params.backtrack)
 		{
 			return self.step_no_backtracking(first, last, level, old_gain, old_g_star)
 		}
@@ -230,7 +389,7 @@ BBData
 
 			// Compute the change in tour length
 			let gain = old_gain - diff;
-			let val  = gain - self.tsp_data.get_distance_between_via_id(new_last, first);
+			let val  = gain - self.data.tsp_data.get_distance_between_via_id(new_last, first);
 
 			if val > g_star
 			{
@@ -240,10 +399,10 @@ BBData
 
 			self.flipper.as_mut().unwrap().flip(last, new_last);
 
-			if (level < Self::MAX_DEPTH)
+			if (level < self.data.config.lk_params.max_depth)
 			{
 				let added_edge   = BBEdge::new_weightless(last, this);
-				let deleted_edge = edge.clone();
+				let deleted_edge = *edge;
 
 				self.mark_edge_as_added(&added_edge);
 				self.mark_edge_as_deleted(&deleted_edge);
@@ -296,7 +455,7 @@ BBData
 			let new_other = edge.end;
 
 			let gain = old_gain - diff;
-			let val = gain - self.tsp_data.get_distance_between_via_id(new_other, if is_mak_morton_edge { last } else { first } );
+			let val = gain - self.data.tsp_data.get_distance_between_via_id(new_other, if is_mak_morton_edge { last } else { first } );
 			if val > g_star
 			{
 				g_star = val;
@@ -315,7 +474,7 @@ BBData
 				self.flipper.as_mut().unwrap().flip(last, new_other);
 			}
 
-			if (level < Self::MAX_DEPTH)
+			if (level < self.data.config.lk_params.max_depth)
 			{
 				if is_mak_morton_edge
 				{
@@ -440,7 +599,7 @@ BBData
 				if seq2
 				{
 					let gain = old_gain - diff2;
-					let val  = gain - self.tsp_data.get_distance_between_via_id(t6, t1);
+					let val  = gain - self.data.tsp_data.get_distance_between_via_id(t6, t1);
 
 					if val > g_star
 					{
@@ -512,7 +671,7 @@ BBData
 						let t8 = edge3.end;
 
 						let gain = t_g - diff3;
-						let val  = gain - self.tsp_data.get_distance_between_via_id(t8, t1);
+						let val  = gain - self.data.tsp_data.get_distance_between_via_id(t8, t1);
 
 						if val > g_star
 						{
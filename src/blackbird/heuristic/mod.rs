@@ -1,5 +1,7 @@
-mod breadth;
 mod ordering;
-mod kick;
-mod sparse_edge_map;
+pub mod kick;
+pub mod sparse_edge_map;
+mod alpha_nearness;
 pub mod main_heuristic;
+pub mod lk_params;
+pub mod tour_merging;
@@ -1,9 +1,7 @@
-use crate::{blackbird::data::{bb_data::BBData, bb_edge::BBEdge}, tsp_lib::node::{TSPNodeID, TSPWeight}};
+use crate::{blackbird::data::{bb_search::BBSearch, bb_edge::BBEdge, flipper::Flipper}, tsp_lib::node::{TSPNodeID, TSPWeight}};
 
-use super::breadth::breadth;
-
-impl
-BBData
+impl<F: Flipper>
+BBSearch<'_, F>
 {
 	/// Based on the CONCORDE function 'look_ahead', defined in 'linkern.c'
 	pub fn
@@ -20,9 +18,9 @@ BBData
 	{
 		let mut ordering = Vec::new();
 
-		for this in &self.sparse_edge_map[&last]
+		for this in &self.data.sparse_edge_map[&last]
 		{
-			let edge = BBEdge::new(&self.tsp_data, last, *this);
+			let edge = BBEdge::new(self.data.tsp_data, last, *this);
 
 			if edge.weight > gain
 			{
@@ -37,9 +35,9 @@ BBData
 			)
 			{
 				let prev = self.flipper.as_ref().unwrap().prev(this);
-				let other_edge = BBEdge::new(&self.tsp_data, *this, prev);
+				let other_edge = BBEdge::new(self.data.tsp_data, *this, prev);
 
-				if !self.is_edge_added(&other_edge)
+				if !self.is_edge_added(&other_edge) && !self.data.is_edge_fixed(&other_edge)
 				{
 					let value = edge.weight - other_edge.weight;
 					ordering.push((other_edge, value));	
@@ -48,12 +46,12 @@ BBData
 		}
 
 		// Sort by value 'val'
-		ordering.sort_by(|(_, a), (_, b)| a.partial_cmp(&b).unwrap());
+		ordering.sort_by(|(_, a), (_, b)| a.partial_cmp(b).unwrap());
 
 		// Return only as many as the breadth as this level allows it
-		if ordering.len() > breadth(level)
+		if ordering.len() > self.data.config.lk_params.breadth(level)
 		{
-			return ordering[..breadth(level)].to_vec();
+			return ordering[..self.data.config.lk_params.breadth(level)].to_vec();
 		}
 		else
 		{
@@ -74,9 +72,9 @@ BBData
 		let mut mak_morton_edge = false;
 		let mut diff = TSPWeight::MAX;
 
-		for this in &self.sparse_edge_map[&last]
+		for this in &self.data.sparse_edge_map[&last]
 		{
-			let edge = BBEdge::new(&self.tsp_data, *this, last);
+			let edge = BBEdge::new(self.data.tsp_data, *this, last);
 
 			if
 			(
@@ -86,9 +84,9 @@ BBData
 			)
 			{
 				let prev = self.flipper.as_ref().unwrap().prev(this);
-				let prev_edge = BBEdge::new(&self.tsp_data, *this, prev);
+				let prev_edge = BBEdge::new(self.data.tsp_data, *this, prev);
 
-				if (!self.is_edge_added(&prev_edge))
+				if (!self.is_edge_added(&prev_edge) && !self.data.is_edge_fixed(&prev_edge))
 				{
 					let value = edge.weight - prev_edge.weight;
 
@@ -103,9 +101,9 @@ BBData
 
 		let first_prev = self.flipper.as_ref().unwrap().prev(&first);
 
-		for this in &self.sparse_edge_map[&first]
+		for this in &self.data.sparse_edge_map[&first]
 		{
-			let edge = BBEdge::new(&self.tsp_data, *this, first);
+			let edge = BBEdge::new(self.data.tsp_data, *this, first);
 
 			if
 			(
@@ -115,9 +113,9 @@ BBData
 			)
 			{
 				let next = self.flipper.as_ref().unwrap().next(this);
-				let next_edge = BBEdge::new(&self.tsp_data, *this, next);
+				let next_edge = BBEdge::new(self.data.tsp_data, *this, next);
 
-				if (!self.is_edge_added(&next_edge))
+				if (!self.is_edge_added(&next_edge) && !self.data.is_edge_fixed(&next_edge))
 				{
 					let value = edge.weight - next_edge.weight;
 
@@ -141,9 +139,6 @@ BBData
 
 
 
-	const ALTERNATE_LOOK_AHEAD_MAX_1: usize = 4;
-	const ALTERNATE_LOOK_AHEAD_MAX_2: usize = 3;
-	const ALTERNATE_LOOK_AHEAD_MAX_3: usize = 3;
 
 
 
@@ -159,14 +154,14 @@ BBData
 	{
 		let mut ordering = Vec::new();
 
-		for this in &self.sparse_edge_map[&t2]
+		for this in &self.data.sparse_edge_map[&t2]
 		{
 			if this == &t1
 			{
 				continue;
 			}
 
-			let t2_this_edge = BBEdge::new(&self.tsp_data, t2, *this);
+			let t2_this_edge = BBEdge::new(self.data.tsp_data, t2, *this);
 
 			if t2_this_edge.weight > gain
 			{
@@ -174,19 +169,23 @@ BBData
 			}
 
 			let next = self.flipper.as_ref().unwrap().next(this);
-			let edge = BBEdge::new(&self.tsp_data, *this, next);
+			let edge = BBEdge::new(self.data.tsp_data, *this, next);
+			if self.data.is_edge_fixed(&edge)
+			{
+				continue;
+			}
 			let val  = t2_this_edge.weight - edge.weight;
 
 			ordering.push((edge, val));
 		}
 
 		// Sort by value 'val'
-		ordering.sort_by(|(_, a), (_, b)| a.partial_cmp(&b).unwrap());
+		ordering.sort_by(|(_, a), (_, b)| a.partial_cmp(b).unwrap());
 
 		// Return only as many as the breadth as allowed for this look ahead type
-		if ordering.len() > Self::ALTERNATE_LOOK_AHEAD_MAX_1
+		if ordering.len() > self.data.config.lk_params.alternate_look_ahead_max_1
 		{
-			return ordering[..Self::ALTERNATE_LOOK_AHEAD_MAX_1].to_vec();
+			return ordering[..self.data.config.lk_params.alternate_look_ahead_max_1].to_vec();
 		}
 		else
 		{
@@ -210,11 +209,11 @@ BBData
 		// - The values 'val' 'seq' and 'side'
 		let mut ordering = Vec::new();
 
-		for t5 in &self.sparse_edge_map[&t4]
+		for t5 in &self.data.sparse_edge_map[&t4]
 		{
 			if self.weirdmark.get(t5).unwrap_or(&0) != &self.weirdmagic
 			{
-				let t4_t5_edge = BBEdge::new(&self.tsp_data, t4, *t5);
+				let t4_t5_edge = BBEdge::new(self.data.tsp_data, t4, *t5);
 
 				if t4_t5_edge.weight > gain
 				{
@@ -225,10 +224,13 @@ BBData
 
 				if t2 == t6 || t3 == t6 { continue; }
 
-				let mut t5_t6_edge = BBEdge::new(&self.tsp_data, *t5, t6);
+				let mut t5_t6_edge = BBEdge::new(self.data.tsp_data, *t5, t6);
 				let mut val        = t4_t5_edge.weight - t5_t6_edge.weight;
 				let     seq        = self.flipper.as_ref().unwrap().sequence(&t2, t5, &t3);
-				ordering.push((t5_t6_edge, val, seq, false));
+				if !self.data.is_edge_fixed(&t5_t6_edge)
+				{
+					ordering.push((t5_t6_edge, val, seq, false));
+				}
 
 				if self.flipper.as_ref().unwrap().sequence(&t2, t5, &t3)
 				{
@@ -236,20 +238,23 @@ BBData
 
 					if t2 == t6 || t3 == t6 { continue; }
 
-					t5_t6_edge = BBEdge::new(&self.tsp_data, *t5, t6);
+					t5_t6_edge = BBEdge::new(self.data.tsp_data, *t5, t6);
 					val        = t4_t5_edge.weight - t5_t6_edge.weight;
-					ordering.push((t5_t6_edge, val, seq, true));
+					if !self.data.is_edge_fixed(&t5_t6_edge)
+					{
+						ordering.push((t5_t6_edge, val, seq, true));
+					}
 				}
 			}
 		}
 
 		// Sort by value 'val'
-		ordering.sort_by(|(_, a, _, _), (_, b, _, _)| a.partial_cmp(&b).unwrap());
+		ordering.sort_by(|(_, a, _, _), (_, b, _, _)| a.partial_cmp(b).unwrap());
 
 		// Return only as many as the breadth as allowed for this look ahead type
-		if ordering.len() > Self::ALTERNATE_LOOK_AHEAD_MAX_2
+		if ordering.len() > self.data.config.lk_params.alternate_look_ahead_max_2
 		{
-			return ordering[..Self::ALTERNATE_LOOK_AHEAD_MAX_2].to_vec();
+			return ordering[..self.data.config.lk_params.alternate_look_ahead_max_2].to_vec();
 		}
 		else
 		{
@@ -273,9 +278,9 @@ BBData
 		// - The values 'val' and 'side'
 		let mut ordering = Vec::new();
 
-		for t7 in &self.sparse_edge_map[&t6]
+		for t7 in &self.data.sparse_edge_map[&t6]
 		{
-			let t6_t7_edge = BBEdge::new(&self.tsp_data, t6, *t7);
+			let t6_t7_edge = BBEdge::new(self.data.tsp_data, t6, *t7);
 
 			if t6_t7_edge.weight > gain
 			{
@@ -292,9 +297,12 @@ BBData
 
 				if t2 != t8_prev && t3 != t8_prev
 				{
-					let t7_t8_prev_edge = BBEdge::new(&self.tsp_data, *t7, t8_prev);
+					let t7_t8_prev_edge = BBEdge::new(self.data.tsp_data, *t7, t8_prev);
 					let val_prev        = t6_t7_edge.weight - t7_t8_prev_edge.weight;
-					ordering.push((t7_t8_prev_edge, val_prev, false));
+					if !self.data.is_edge_fixed(&t7_t8_prev_edge)
+					{
+						ordering.push((t7_t8_prev_edge, val_prev, false));
+					}
 				}
 
 
@@ -302,20 +310,23 @@ BBData
 
 				if t2 != t8_next && t3 != t8_next
 				{
-					let t7_t8_next_edge = BBEdge::new(&self.tsp_data, *t7, t8_next);
+					let t7_t8_next_edge = BBEdge::new(self.data.tsp_data, *t7, t8_next);
 					let val_next        = t6_t7_edge.weight - t7_t8_next_edge.weight;
-					ordering.push((t7_t8_next_edge, val_next, true));
+					if !self.data.is_edge_fixed(&t7_t8_next_edge)
+					{
+						ordering.push((t7_t8_next_edge, val_next, true));
+					}
 				}
 			}
 		}
 
 		// Sort by value 'val'
-		ordering.sort_by(|(_, a, _), (_, b, _)| a.partial_cmp(&b).unwrap());
+		ordering.sort_by(|(_, a, _), (_, b, _)| a.partial_cmp(b).unwrap());
 
 		// Return only as many as the breadth as allowed for this look ahead type
-		if ordering.len() > Self::ALTERNATE_LOOK_AHEAD_MAX_3
+		if ordering.len() > self.data.config.lk_params.alternate_look_ahead_max_3
 		{
-			return ordering[..Self::ALTERNATE_LOOK_AHEAD_MAX_3].to_vec();
+			return ordering[..self.data.config.lk_params.alternate_look_ahead_max_3].to_vec();
 		}
 		else
 		{
@@ -1,9 +1,60 @@
 use std::collections::HashMap;
+use std::str::FromStr;
+use std::time::Instant;
+
 use crate::blackbird::data::bb_data::BBData;
+use crate::blackbird::data::bb_spanning_tree::NO_PARENT;
+use crate::blackbird::delaunay::triangulation::DelaunayTriangulation;
 use crate::tsp_lib::node::TSPNodeID;
+use crate::warn;
+
+/// This enum describes how the sparse set of good edges gets computed, which
+/// are the only edges considered by LK for adding them to the tour. Multiple
+/// methods can be combined, in which case the union of their edges is used
+#[derive(Clone, Debug, PartialEq)]
+pub enum
+ESparseEdgeMethod
+{
+	/// The nearest nodes in each quadrant (or octant) around a node
+	QuadrantNearest,
+
+	/// The nearest nodes around a node, regardless of their direction
+	Nearest,
+
+	/// The edges of the Delaunay triangulation (2-dimensional instances only)
+	Delaunay,
+
+	/// The edges with the smallest alpha values, see alpha_nearness.rs
+	AlphaNearness,
+}
+
+impl
+FromStr
+for
+ESparseEdgeMethod
+{
+	type Err = ();
+
+	fn 
+	from_str
+	(
+		s: &str
+	) 
+	-> Result<Self, Self::Err>
+	{
+		match s.to_lowercase().as_str().chars().nth(0).unwrap_or(' ')
+		{
+			'q'                        => Ok(ESparseEdgeMethod::QuadrantNearest),
+			'n'                        => Ok(ESparseEdgeMethod::Nearest),
+			'd'                        => Ok(ESparseEdgeMethod::Delaunay),
+			'a'                        => Ok(ESparseEdgeMethod::AlphaNearness),
+			_                          => Err(())
+		}
+	}
+}
 
-impl 
-BBData
+impl
+BBData<'_>
 {
 	/// This computes the sparse edge set used by the heuristic at multiple
 	/// points.
@@ -20,25 +71,211 @@ BBData
 			self.construct_kd_tree();
 		}
 
+		// The union of the good edges of all the given methods
+		let mut sparse_edge_map: HashMap<TSPNodeID, Vec<TSPNodeID>> = HashMap::new();
+		// Without any given method, use the quadrant nearest edges
+		let methods = if self.config.sparse_edge_methods.is_empty() { vec![ESparseEdgeMethod::QuadrantNearest] }
+			else { self.config.sparse_edge_methods.clone() };
+
+		for mut method in methods
+		{
+			// The Delaunay triangulation requires a 2-dimensional instance
+			if method == ESparseEdgeMethod::Delaunay
+			&& (!self.tsp_data.distance.is_geometric() || self.tsp_data.distance.dimensions() != 2)
+			{
+				warn!("Delaunay edges require 2-dimensional coordinates, using quadrant nearest edges instead!");
+				method = ESparseEdgeMethod::QuadrantNearest;
+			}
+
+			let method_sparse_edge_map = match method
+			{
+				ESparseEdgeMethod::QuadrantNearest  => self.quadrant_nearest_edges(),
+				ESparseEdgeMethod::Nearest          => self.nearest_edges(),
+				ESparseEdgeMethod::Delaunay         => self.delaunay_edges(),
+				ESparseEdgeMethod::AlphaNearness    => self.alpha_nearness_edges(),
+			};
+
+			for (node_id, other_nodes) in method_sparse_edge_map
+			{
+				let node_specific_sparse_edge_map = sparse_edge_map.entry(node_id).or_default();
+				for other_node_id in other_nodes
+				{
+					if !node_specific_sparse_edge_map.contains(&other_node_id)
+					{
+						node_specific_sparse_edge_map.push(other_node_id);
+					}
+				}
+			}
+		}
+
+		// Sort the good edges by their weight
+		for node_id in 0..self.tsp_data.n
+		{
+			let mut to_be_sorted = sparse_edge_map.get(&node_id).cloned().unwrap_or_default();
+			to_be_sorted.sort_by(|a, b| 
+				self.tsp_data.get_distance_between_via_id(node_id, *a)
+				.partial_cmp(
+					&self.tsp_data.get_distance_between_via_id(node_id, *b)
+				).unwrap()
+			);
+
+			sparse_edge_map.insert(node_id, to_be_sorted);
+		}
+
+		self.sparse_edge_map = sparse_edge_map;
+
+		self.make_symmetric();
+
+		// The walk kick needs to reach all nodes via the good edges, which is
+		// not guaranteed by every method (e.g. the nearest edges of clustered
+		// instances), so the components get connected if necessary
+		if !self.time_limit_reached()
+		{
+			self.connect_sparse_edge_map();
+		}
+
+		if (self.config.verbose)
+		{
+			let degrees = self.sparse_edge_map.values().map(|other_nodes| other_nodes.len());
+			println!("Sparse edge map average degree : {}", degrees.clone().sum::<usize>() as f64 / self.tsp_data.n as f64);
+			println!("Sparse edge map maximum degree : {}", degrees.max().unwrap_or(0));
+		}
+	}
+
+	/// Computes the good edges to the nearest nodes using the KD tree
+	fn
+	nearest_edges
+	(
+		&self
+	)
+	-> HashMap<TSPNodeID, Vec<TSPNodeID>>
+	{
+		let mut sparse_edge_map: HashMap<TSPNodeID, Vec<TSPNodeID>> = HashMap::new();
+
+		for node_id in 0..self.tsp_data.n
+		{
+			// The sparse edge set is of no use once the time limit is reached,
+			// as CLK gets skipped then
+			if self.time_limit_reached()
+			{
+				break;
+			}
+
+			let other_nodes = self.kd_tree.as_ref().unwrap().nearests(
+				&self.tsp_data.nodes[node_id],
+				self.config.nearest_count,
+				self.tsp_data
+			).iter().map(|&(node, _)| node.id).filter(|other_node_id| other_node_id != &node_id).collect();
+
+			sparse_edge_map.insert(node_id, other_nodes);
+		}
+
+		return sparse_edge_map;
+	}
+
+	/// Computes the good edges given by the Delaunay triangulation
+	pub fn
+	delaunay_edges
+	(
+		&self
+	)
+	-> HashMap<TSPNodeID, Vec<TSPNodeID>>
+	{
+		// Start time measurement
+		let time_measurement_start = Instant::now();
+
+		let mut sparse_edge_map: HashMap<TSPNodeID, Vec<TSPNodeID>> = HashMap::new();
+
+		for (a, b) in DelaunayTriangulation::new(self.tsp_data).edges()
+		{
+			sparse_edge_map.entry(a).or_default().push(b);
+			sparse_edge_map.entry(b).or_default().push(a);
+		}
+
+		if (self.config.verbose)
+		{
+			println!("Delaunay Triangulation Time : {}", (time_measurement_start.elapsed().as_micros() as f64) / 1000000.0);
+		}
+
+		return sparse_edge_map;
+	}
+
+	/// Computes the adjacency lists of the Delaunay triangulation, which are
+	/// only available for 2-dimensional instances
+	pub fn
+	delaunay_adjacency
+	(
+		&self
+	)
+	-> Option<Vec<Vec<TSPNodeID>>>
+	{
+		if !self.tsp_data.distance.is_geometric() || self.tsp_data.distance.dimensions() != 2
+		{
+			return None;
+		}
+
+		let mut adjacency = vec![Vec::new(); self.tsp_data.n];
+		for (node_id, other_nodes) in self.delaunay_edges()
+		{
+			for other_node_id in other_nodes
+			{
+				adjacency[node_id].push(other_node_id);
+				adjacency[other_node_id].push(node_id);
+			}
+		}
+
+		return Some(adjacency);
+	}
+
+	/// Computes the good edges to the quadrant nearest nodes (or just the
+	/// nearest nodes for non-geometric instances) using the KD tree
+	pub fn
+	quadrant_nearest_edges
+	(
+		&self
+	)
+	-> HashMap<TSPNodeID, Vec<TSPNodeID>>
+	{
 		// Collect in the following table the good edges
 		// The key marks on of the nodes of an edge, the value is a list of 
 		// end nodes of the good edges, combined with the weight of the edge
 		let mut sparse_edge_map: HashMap<TSPNodeID, Vec<TSPNodeID>> = HashMap::new();
 
-		let k = self.cli_args.quadrant_nearest_count;
-		let goal = 4 * k; // x4 due to quadrants
+		let k = self.config.quadrant_nearest_count;
+		let goal = (1 << self.tsp_data.distance.dimensions()) * k; // x4 due to quadrants (x8 for octants)
 
 		// Compute the good edges for all nodes
 		for node_id in 0..self.tsp_data.n
 		{
+			// The sparse edge set is of no use once the time limit is reached,
+			// as CLK gets skipped then
+			if self.time_limit_reached()
+			{
+				break;
+			}
+
 			let mut node_specific_sparse_edge_map = sparse_edge_map.get(&node_id).unwrap_or(&Vec::new()).clone();	
 
 			// Get for each quadrant the k nearest
-			let other_nodes: Vec<TSPNodeID> = self.kd_tree.as_ref().unwrap().all_quadrant_nearest(
-				&self.tsp_data.nodes[node_id], 
-				k,
-				&self.tsp_data
-			).iter().map(|&(node, _)| node.id).collect();
+			// Without a geometric interpretation of the distances, quadrants
+			// make no sense. Instead, use the nearest nodes as given by the
+			// weight matrix (which is what the KD tree falls back to)
+			let other_nodes: Vec<TSPNodeID> = if self.tsp_data.distance.is_geometric()
+			{
+				self.kd_tree.as_ref().unwrap().all_quadrant_nearest(
+					&self.tsp_data.nodes[node_id], 
+					k,
+					self.tsp_data
+				)
+			}
+			else
+			{
+				self.kd_tree.as_ref().unwrap().nearests(
+					&self.tsp_data.nodes[node_id], 
+					goal,
+					self.tsp_data
+				)
+			}.iter().map(|&(node, _)| node.id).collect();
 
 			// Add to the vector of this node and the other node
 			for other_node_id in &other_nodes[..std::cmp::min(goal, other_nodes.len())]
@@ -46,7 +283,7 @@ BBData
 				if other_node_id < &node_id
 				{
 					// For the other node
-					if let Some(other_node_vec) = sparse_edge_map.get(&other_node_id)
+					if let Some(other_node_vec) = sparse_edge_map.get(other_node_id)
 					{
 						if !other_node_vec.contains(&node_id)
 						{
@@ -74,23 +311,7 @@ BBData
 			sparse_edge_map.insert(node_id, node_specific_sparse_edge_map);
 		}
 
-		// Sort the good edges by their weight
-		for node_id in 0..self.tsp_data.n
-		{
-			let mut to_be_sorted = sparse_edge_map[&node_id].clone();
-			to_be_sorted.sort_by(|a, b| 
-				self.tsp_data.get_distance_between_via_id(node_id, *a)
-				.partial_cmp(
-					&self.tsp_data.get_distance_between_via_id(node_id, *b)
-				).unwrap()
-			);
-
-			sparse_edge_map.insert(node_id, to_be_sorted);
-		}
-
-		self.sparse_edge_map = sparse_edge_map;
-
-		self.make_symmetric();
+		return sparse_edge_map;
 	}
 
 	/// Due to how the sparse edge map is generated and subsequently accessed
@@ -103,7 +324,7 @@ BBData
 		&mut self
 	)
 	{
-		let keys = self.sparse_edge_map.keys().map(|a| *a).collect::<Vec<TSPNodeID>>();
+		let keys = self.sparse_edge_map.keys().copied().collect::<Vec<TSPNodeID>>();
 		for node_id in keys
 		{
 			let other_nodes = self.sparse_edge_map[&node_id].clone();
@@ -124,5 +345,55 @@ BBData
 			}
 		}
 	}
-}
 
+	/// Adds the edges of a spanning tree to the sparse edge map if the good
+	/// edges do not connect all nodes. For 2-dimensional instances, the tree
+	/// is computed on the Delaunay edges if possible, otherwise on all edges
+	fn
+	connect_sparse_edge_map
+	(
+		&mut self
+	)
+	{
+		let n = self.tsp_data.n;
+		let penalties = vec![0.0; n];
+
+		let adjacency = (0..n).map(|node_id| self.sparse_edge_map[&node_id].clone()).collect::<Vec<Vec<TSPNodeID>>>();
+		if self.minimum_spanning_tree(&penalties, &Some(adjacency)).is_some()
+		{
+			return;
+		}
+
+		if (self.config.verbose)
+		{
+			println!("Sparse edge map is not connected, adding the edges of a spanning tree");
+		}
+
+		let tree = match self.minimum_spanning_tree(&penalties, &self.delaunay_adjacency())
+		{
+			Some(tree) => tree,
+			None       => self.minimum_spanning_tree(&penalties, &None).unwrap(),
+		};
+
+		for node_id in 0..n
+		{
+			let parent = tree.parent[node_id];
+			if parent == NO_PARENT || self.sparse_edge_map[&node_id].contains(&parent)
+			{
+				continue;
+			}
+
+			let mut other_node_list = self.sparse_edge_map[&node_id].clone();
+			other_node_list.push(parent);
+			other_node_list.sort_by(|a, b|
+				self.tsp_data.get_distance_between_via_id(node_id, *a)
+				.partial_cmp(
+					&self.tsp_data.get_distance_between_via_id(node_id, *b)
+				).unwrap()
+			);
+			self.sparse_edge_map.insert(node_id, other_node_list);
+		}
+
+		self.make_symmetric();
+	}
+}
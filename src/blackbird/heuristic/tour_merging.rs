@@ -0,0 +1,111 @@
+use std::collections::HashMap;
+use std::collections::HashSet;
+
+use crate::blackbird::data::bb_data::BBData;
+use crate::blackbird::data::bb_edge::BBEdge;
+use crate::blackbird::data::bb_search::BBSearch;
+use crate::blackbird::data::bb_tour::BBTour;
+use crate::blackbird::data::flipper::Flipper;
+use crate::tsp_lib::node::TSPNodeID;
+use crate::tsp_lib::node::TSPWeight;
+use crate::warn;
+
+impl
+BBData<'_>
+{
+	/// Merges the tours found by the trials of CLK, similar to the tour
+	/// merging of CONCORDE. The edges common to all tours get fixed and CLK
+	/// is applied once more, starting from the best of the tours but only
+	/// considering the edges of the union graph of all tours as good edges.
+	/// Returns the merged tour together with its length, which is never
+	/// worse than the best of the given tours, and the number of kicks that
+	/// got dropped as they would have removed fixed edges
+	pub fn
+	merge_tours<F: Flipper>
+	(
+		&self,
+		tours:                         &[&Vec<TSPNodeID>],
+		best_tour:                     &[TSPNodeID],
+		seed:                          u64,
+	)
+	-> (Vec<TSPNodeID>, TSPWeight, usize)
+	{
+		if self.config.kick_type.with_fixed_edges() != self.config.kick_type
+		{
+			warn!(format!("The {:?} kick can't keep fixed edges, using the WALK kick for tour merging instead!", self.config.kick_type));
+		}
+
+		// Count for each edge how many of the tours contain it
+		let mut edge_counts: HashMap<BBEdge, usize> = HashMap::new();
+		for tour in tours
+		{
+			for i in 0..tour.len()
+			{
+				let edge = BBEdge::new(self.tsp_data, tour[i], tour[(i+1) % tour.len()]);
+				*edge_counts.entry(edge).or_insert(0) += 1;
+			}
+		}
+
+		// The union graph replaces the sparse edge map, sorted by weight
+		let mut union_graph: HashMap<TSPNodeID, Vec<TSPNodeID>> = HashMap::new();
+		for edge in edge_counts.keys()
+		{
+			union_graph.entry(edge.start).or_default().push(edge.end);
+			union_graph.entry(edge.end).or_default().push(edge.start);
+		}
+		for (node_id, other_nodes) in union_graph.iter_mut()
+		{
+			other_nodes.sort_by(|a, b|
+				self.tsp_data.get_distance_between_via_id(*node_id, *a)
+				.partial_cmp(
+					&self.tsp_data.get_distance_between_via_id(*node_id, *b)
+				).unwrap()
+			);
+		}
+
+		let fixed_edges = edge_counts.iter()
+			.filter(|(_, count)| **count == tours.len())
+			.map(|(edge, _)| *edge)
+			.collect::<HashSet<BBEdge>>();
+
+		if (self.config.verbose)
+		{
+			println!("Fixed edges : {} / {}", fixed_edges.len(), self.tsp_data.n);
+			println!("Union graph edges : {}", edge_counts.len());
+		}
+
+		let mut best_bb_tour = BBTour::new();
+		for i in 0..best_tour.len()
+		{
+			best_bb_tour.add(BBEdge::new(self.tsp_data, best_tour[i], best_tour[(i+1) % best_tour.len()]));
+		}
+
+		// The reduced problem shares the instance and the settings but uses
+		// its own good edges and starts from the best tour
+		let reduced_data = BBData
+		{
+			tsp_data:                  self.tsp_data,
+			sparse_edge_map:           union_graph,
+			config:                    self.config.clone(),
+			solve_start:               self.solve_start,
+			kd_tree:                   self.kd_tree.clone(),
+
+			initial_tour_length:       best_bb_tour.compute_len(),
+			current_tour:              Some(best_bb_tour),
+			initial_tour_cycle:        Some(best_tour.to_vec()),
+			fixed_edges:               fixed_edges,
+
+			random_generator:          rand_seeder::Seeder::from(seed).make_rng(),
+		};
+
+		let mut search = BBSearch::<F>::new(&reduced_data, seed);
+		search.chained_lin_kernighan();
+
+		if search.dropped_kick_count > 0
+		{
+			warn!(format!("{} of {} kicks during tour merging were dropped as they would have removed fixed edges!", search.dropped_kick_count, search.kick_count));
+		}
+
+		return (search.output_tour_cycle.take().unwrap(), search.output_tour_length, search.dropped_kick_count);
+	}
+}
@@ -8,7 +8,7 @@ use crate::blackbird::data::bb_data::BBData;
 use crate::tsp_lib::node::*;
 
 impl
-BBData
+BBData<'_>
 {
 	pub fn
 	create_initial_tour_boruvka
@@ -22,7 +22,10 @@ BBData
 		// doesn't exist yet
 		self.construct_kd_tree();
 
-		println!("Time for constructing KD tree: {}µs", time.elapsed().as_micros());
+		if (self.config.verbose)
+		{
+			println!("Time for constructing KD tree: {}µs", time.elapsed().as_micros());
+		}
 
 		// Defining some helper structures:
 
@@ -72,29 +75,29 @@ BBData
 						let tail_node = self.tsp_data.get_node(tails[&node.id]);
 						local_kd_tree.disable_node(tail_node);
 
-						let nearest_neighbor_candidates = local_kd_tree.nearests(node, 1, &self.tsp_data);
+						let nearest_neighbor_candidates = local_kd_tree.nearests(node, 1, self.tsp_data);
 
 						// If no such node exists, we are done an only have one
 						// final edge left to add to close the tour
-						if nearest_neighbor_candidates.first().is_none()
+						if nearest_neighbor_candidates.is_empty()
 						{
 							break;
 						}
 
 						// Unpack the nearest neighbor an re-enable the tail as
 						// it still needs a second edge
-						nearest_neighbor_with_distance = nearest_neighbor_candidates.first().unwrap().clone();
+						nearest_neighbor_with_distance = *nearest_neighbor_candidates.first().unwrap();
 						local_kd_tree.enable_node(tail_node);
 					}
 					else
 					{
-						nearest_neighbor_with_distance = local_kd_tree.nearests(node, 1, &self.tsp_data).first().unwrap().clone();
+						nearest_neighbor_with_distance = *local_kd_tree.nearests(node, 1, self.tsp_data).first().unwrap();
 					}
 
 					nearest_neighbors.insert(node.id,
 						(
 							nearest_neighbor_with_distance.1,
-							Some(nearest_neighbor_with_distance.0.clone())
+							Some(nearest_neighbor_with_distance.0)
 						)
 					);
 
@@ -128,23 +131,21 @@ BBData
 				// (if it already has degree 2 then we can't add an edge for it)
 				if degree[&node.id] < 2
 				{
-					let nearest_neighbor = nearest_neighbors[&node.id].1.unwrap().clone();
+					let nearest_neighbor = nearest_neighbors[&node.id].1.unwrap();
 
 					if degree[&nearest_neighbor.id] != 2 // && tails[&node.id] != nearest_neighbor.id
 					{
 						if tails.contains_key(&node.id)
-						{
-							if tails[&node.id] == nearest_neighbor.id
+							&& tails[&node.id] == nearest_neighbor.id
 							{
 								continue;
 							}
-						}
 
 						// Remove nodes from the KD tree if they already have a
 						// degree greater than 0
 						if degree.get(&node.id).unwrap() > &0
 						{
-							local_kd_tree.disable_node(&node);
+							local_kd_tree.disable_node(node);
 						}
 
 						if degree.get(&nearest_neighbor.id).unwrap() > &0
@@ -157,7 +158,7 @@ BBData
 						degree.insert(nearest_neighbor.id, degree[&nearest_neighbor.id] + 1);
 
 						// Insert edge
-						let new_edge = BBEdge::new(&self.tsp_data, node.id, nearest_neighbor.id);
+						let new_edge = BBEdge::new(self.tsp_data, node.id, nearest_neighbor.id);
 						new_tour.add(new_edge);
 
 						// Handle the tails
@@ -202,7 +203,7 @@ BBData
 		assert!(lonely_nodes.len() == 2);
 
 		// Add the final edge to complete the tour
-		let new_edge = BBEdge::new(&self.tsp_data, lonely_nodes[0].id, lonely_nodes[1].id);
+		let new_edge = BBEdge::new(self.tsp_data, lonely_nodes[0].id, lonely_nodes[1].id);
 		new_tour.add(new_edge);
 
 		// Place new tour in BBData struct
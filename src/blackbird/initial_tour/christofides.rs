@@ -0,0 +1,243 @@
+use crate::blackbird::data::bb_data::BBData;
+use crate::blackbird::data::bb_edge::BBEdge;
+use crate::blackbird::data::bb_spanning_tree::NO_PARENT;
+use crate::blackbird::data::bb_tour::BBTour;
+use crate::tsp_lib::node::TSPNodeID;
+use crate::tsp_lib::node::TSPWeight;
+
+use super::matching::BBBlossomMatching;
+
+/// The maximum number of odd degree nodes for which a minimum weight perfect
+/// matching gets computed. For more nodes, a greedy matching is used instead
+const EXACT_MATCHING_MAX: usize = 500;
+
+/// The largest edge weight after scaling the distances to integers for the
+/// exact matching
+const MATCHING_WEIGHT_SCALE: TSPWeight = 1000000.0;
+
+impl
+BBData<'_>
+{
+	/// Implements the tour construction by Christofides, which computes a
+	/// minimum spanning tree, adds a minimum weight perfect matching on the
+	/// nodes with odd degree in the tree, and shortcuts an Euler tour of the
+	/// resulting multigraph to get a tour
+	/// For 2D euclidean instances, the spanning tree is computed on the
+	/// Delaunay edges, which always contain a minimum spanning tree. For all
+	/// other instances, it is computed on the (connected) sparse edge map,
+	/// which avoids the quadratic runtime of using all edges but only gives
+	/// an approximately minimum spanning tree
+	pub fn
+	create_initial_tour_christofides
+	(
+		&mut self
+	)
+	{
+		let n = self.tsp_data.n;
+
+		let adjacency = if self.tsp_data.distance.is_euclidean_2d()
+		{
+			self.delaunay_adjacency()
+		}
+		else
+		{
+			// The sparse edge map is required by CLK afterwards anyway
+			if self.sparse_edge_map.is_empty()
+			{
+				self.sparse_edge_map();
+			}
+			Some((0..n).map(|node_id| self.sparse_edge_map.get(&node_id).cloned().unwrap_or_default()).collect())
+		};
+
+		// Only if the time limit stopped the computation of the sparse edge
+		// map early, its edges might not connect all nodes
+		let tree = match self.minimum_spanning_tree(&vec![0.0; n], &adjacency)
+		{
+			Some(tree) => tree,
+			None       => self.minimum_spanning_tree(&vec![0.0; n], &None).unwrap(),
+		};
+
+		// The edges of the multigraph, consisting of the tree and the matching
+		let mut edges = Vec::with_capacity(n + n / 2);
+		let mut degree = vec![0; n];
+		for node_id in 0..n
+		{
+			if tree.parent[node_id] != NO_PARENT
+			{
+				edges.push((node_id, tree.parent[node_id]));
+				degree[node_id] += 1;
+				degree[tree.parent[node_id]] += 1;
+			}
+		}
+
+		let odd_nodes = (0..n).filter(|node_id| degree[*node_id] % 2 == 1).collect::<Vec<TSPNodeID>>();
+		if odd_nodes.len() <= EXACT_MATCHING_MAX
+		{
+			edges.extend(self.minimum_weight_perfect_matching(&odd_nodes));
+		}
+		else
+		{
+			edges.extend(self.greedy_perfect_matching(&odd_nodes));
+		}
+
+		// Walk along an Euler tour using Hierholzer's algorithm and skip all
+		// nodes that have been visited before
+		let mut incident_edges = vec![Vec::new(); n];
+		for (edge_index, (node1, node2)) in edges.iter().enumerate()
+		{
+			incident_edges[*node1].push(edge_index);
+			incident_edges[*node2].push(edge_index);
+		}
+
+		let mut used = vec![false; edges.len()];
+		let mut visited = vec![false; n];
+		let mut order = Vec::with_capacity(n);
+		let mut stack = vec![0];
+
+		while let Some(&current) = stack.last()
+		{
+			if let Some(edge_index) = incident_edges[current].pop()
+			{
+				if !used[edge_index]
+				{
+					used[edge_index] = true;
+					let (node1, node2) = edges[edge_index];
+					stack.push(if node1 == current { node2 } else { node1 });
+				}
+			}
+			else
+			{
+				stack.pop();
+				if !visited[current]
+				{
+					visited[current] = true;
+					order.push(current);
+				}
+			}
+		}
+
+		// The new tour that results from this function
+		let mut new_tour = BBTour::new();
+		for i in 1..order.len()
+		{
+			new_tour.add(BBEdge::new(self.tsp_data, order[i-1], order[i]));
+		}
+
+		// Close the tour, which for two nodes results in the second edge
+		// between them
+		new_tour.add(BBEdge::new(self.tsp_data, order[order.len()-1], order[0]));
+
+		// Place new tour in BBData struct
+		self.current_tour = Some(new_tour);
+	}
+
+	/// Computes a minimum weight perfect matching of the given nodes with
+	/// Edmonds' blossom algorithm. As this works on integer weights that get
+	/// maximized, the distances are scaled and subtracted from a weight large
+	/// enough that every perfect matching outweighs all other matchings
+	fn
+	minimum_weight_perfect_matching
+	(
+		&self,
+		nodes:                         &[TSPNodeID],
+	)
+	-> Vec<(TSPNodeID, TSPNodeID)>
+	{
+		let k = nodes.len();
+
+		let mut max_distance: TSPWeight = 0.0;
+		for i in 0..k
+		{
+			for j in i+1..k
+			{
+				max_distance = max_distance.max(self.tsp_data.get_distance_between_via_id(nodes[i], nodes[j]));
+			}
+		}
+		let scale = if max_distance > 0.0 { MATCHING_WEIGHT_SCALE / max_distance } else { 0.0 };
+		let base = (k as i64 / 2 + 1) * (MATCHING_WEIGHT_SCALE as i64 + 1) + 1;
+
+		let mut matching = BBBlossomMatching::new(k);
+		for i in 0..k
+		{
+			for j in i+1..k
+			{
+				let distance = self.tsp_data.get_distance_between_via_id(nodes[i], nodes[j]);
+				matching.set_weight(i + 1, j + 1, base - (distance * scale).round() as i64);
+			}
+		}
+
+		let mates = matching.solve();
+		return (1..=k)
+			.filter(|i| mates[*i] > *i)
+			.map(|i| (nodes[i - 1], nodes[mates[i] - 1]))
+			.collect();
+	}
+
+	/// Computes a perfect matching of the given nodes greedily in rounds: Find
+	/// for each unmatched node the nearest unmatched node using the KD tree
+	/// and match these pairs in order of increasing distance
+	fn
+	greedy_perfect_matching
+	(
+		&mut self,
+		nodes:                         &[TSPNodeID],
+	)
+	-> Vec<(TSPNodeID, TSPNodeID)>
+	{
+		// Calling this is "safe" as the tree only gets constructed if it
+		// doesn't exist yet
+		self.construct_kd_tree();
+
+		// Make local copy of the KD tree as it gets edited, containing only
+		// the nodes that still need to be matched
+		let mut local_kd_tree = self.kd_tree.as_ref().unwrap().clone();
+		let mut unmatched = vec![false; self.tsp_data.n];
+		for node_id in nodes
+		{
+			unmatched[*node_id] = true;
+		}
+		for node in &self.tsp_data.nodes
+		{
+			if !unmatched[node.id]
+			{
+				local_kd_tree.disable_node(node);
+			}
+		}
+
+		let mut matching = Vec::with_capacity(nodes.len() / 2);
+		let mut remaining = nodes.len();
+
+		while remaining > 1
+		{
+			let mut candidates = Vec::new();
+
+			for node_id in nodes.iter().filter(|node_id| unmatched[**node_id])
+			{
+				let node = self.tsp_data.get_node(*node_id);
+				local_kd_tree.disable_node(node);
+				if let Some((nearest_neighbor, _)) = local_kd_tree.nearests(node, 1, self.tsp_data).first()
+				{
+					candidates.push(BBEdge::new(self.tsp_data, node.id, nearest_neighbor.id));
+				}
+				local_kd_tree.enable_node(node);
+			}
+
+			candidates.sort_by(|a, b| a.weight.partial_cmp(&b.weight).unwrap());
+
+			for edge in &candidates
+			{
+				if unmatched[edge.start] && unmatched[edge.end]
+				{
+					unmatched[edge.start] = false;
+					unmatched[edge.end]   = false;
+					local_kd_tree.disable_node(self.tsp_data.get_node(edge.start));
+					local_kd_tree.disable_node(self.tsp_data.get_node(edge.end));
+					matching.push((edge.start, edge.end));
+					remaining -= 2;
+				}
+			}
+		}
+
+		return matching;
+	}
+}
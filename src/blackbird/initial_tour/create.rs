@@ -1,18 +1,22 @@
 use std::time::Instant;
 
 use crate::blackbird::data::bb_data::*;
+use crate::blackbird::error::SolverError;
 use crate::warn;
 
 use super::method::EInitialTourMethod;
 
 impl
-BBData
+BBData<'_>
 {
+	/// Creates the initial tour with the method given by the configuration
+	/// and validates it
 	pub fn
 	create_initial_tour
 	(
 		&mut self
 	)
+	-> Result<(), SolverError>
 	{
 		if self.current_tour.is_some()
 		{
@@ -21,26 +25,33 @@ BBData
 
 		let time_measurement_start = Instant::now();
 
-		match &self.init_method
+		match &self.config.init_method
 		{
 			EInitialTourMethod::Random           => self.create_initial_tour_random(),
 			EInitialTourMethod::Boruvka          => self.create_initial_tour_boruvka(),
 			EInitialTourMethod::QuickBoruvka     => self.create_initial_tour_quick_boruvka(),	
+			EInitialTourMethod::Greedy           => self.create_initial_tour_greedy(),
+			EInitialTourMethod::NearestNeighbor  => self.create_initial_tour_nearest_neighbor(),
+			EInitialTourMethod::SpaceFillingCurve => self.create_initial_tour_space_filling_curve(),
+			EInitialTourMethod::Christofides     => self.create_initial_tour_christofides(),
+			EInitialTourMethod::NearestInsertion => self.create_initial_tour_nearest_insertion(),
+			EInitialTourMethod::FarthestInsertion => self.create_initial_tour_farthest_insertion(),
+			EInitialTourMethod::CheapestInsertion => self.create_initial_tour_cheapest_insertion(),
+			EInitialTourMethod::RandomInsertion  => self.create_initial_tour_random_insertion(),
+			EInitialTourMethod::FromFile(path)   => self.create_initial_tour_from_file(&path.clone())?,
 		}
 
 		// Validate the tour
-		if let Some(tour_cyle) = self.current_tour.as_ref().unwrap().is_valid(false)
+		let tour_cycle = self.current_tour.as_ref().unwrap().is_valid(self.tsp_data.n, false)
+			.map_err(|error| error.with_external_ids(self.tsp_data))?;
+		self.initial_tour_cycle  = Some(tour_cycle);
+		self.initial_tour_length = self.current_tour.as_ref().unwrap().compute_len();
+
+		if (self.config.verbose)
 		{
-			self.initial_tour_cycle  = Some(tour_cyle);
-			self.current_tour_length = self.current_tour.as_ref().unwrap().compute_len();
-			self.output_tour_cycle  = self.initial_tour_cycle.clone();
-			self.output_tour_length = self.current_tour_length;
-			println!("Initial tour length: {}", self.output_tour_length);
 			println!("Initial tour creation runtime : {}", (time_measurement_start.elapsed().as_micros() as f64) / 1000000.0);
-			
-			return;
 		}
 
-		panic!("Could not create a valid tour for the given init method!");
+		return Ok(());
 	}
 }
\ No newline at end of file
@@ -0,0 +1,46 @@
+use crate::blackbird::data::bb_edge::BBEdge;
+use crate::blackbird::data::bb_tour::BBTour;
+
+use crate::blackbird::data::bb_data::BBData;
+use crate::tsp_lib::error::TSPLibError;
+use crate::tsp_lib::reader::read_tsplib_tour_file;
+
+impl
+BBData<'_>
+{
+	/// Reads the initial tour from the given .tour file. Whether the nodes
+	/// actually form a valid tour is left to the validation of the tour
+	pub fn
+	create_initial_tour_from_file
+	(
+		&mut self,
+		path_string: &String,
+	)
+	-> Result<(), TSPLibError>
+	{
+		// Read in the tour, which is given as sequence of nodes
+		let node_ids = read_tsplib_tour_file(path_string, self.tsp_data)?;
+
+		// Initialize the new tour
+		let mut new_tour = BBTour::new();
+
+		// Construct the edges, including the one that closes the tour
+		// A node following itself results in a loop, which can't be created
+		// via BBEdge::new but gets reported by the validation of the tour
+		for i in 0..node_ids.len()
+		{
+			let next_node_id = node_ids[(i+1) % node_ids.len()];
+			if node_ids[i] == next_node_id
+			{
+				new_tour.add(BBEdge { start: node_ids[i], end: next_node_id, weight: 0.0 });
+				continue;
+			}
+			new_tour.add(BBEdge::new(self.tsp_data, node_ids[i], next_node_id));
+		}
+
+		// Replace current with new tour
+		self.current_tour = Some(new_tour);
+
+		return Ok(());
+	}
+}
@@ -0,0 +1,155 @@
+use crate::blackbird::data::bb_data::BBData;
+use crate::blackbird::data::bb_edge::BBEdge;
+use crate::blackbird::data::bb_tour::BBTour;
+use crate::tsp_lib::node::*;
+
+impl
+BBData<'_>
+{
+	/// Implements the greedy tour creation algorithm, which goes through the
+	/// edges in order of increasing length and adds an edge to the tour if
+	/// both its nodes have degree less than 2 and it does not close a cycle
+	/// Just like CONCORDE's "CCedgegen_greedy_tour", only the edges of the
+	/// sparse edge map are considered at first. The remaining fragments are
+	/// then joined greedily using nearest neighbor searches in the KD tree
+	pub fn
+	create_initial_tour_greedy
+	(
+		&mut self
+	)
+	{
+		// Calling these is "safe" as the sparse edge map and the tree only get
+		// computed if they don't exist yet
+		if self.sparse_edge_map.is_empty()
+		{
+			self.sparse_edge_map();
+		}
+		self.construct_kd_tree();
+
+		// Store the degree of each node
+		let mut degree = vec![0; self.tsp_data.n];
+
+		// Store the other end of the fragment (path) each node belongs to,
+		// which is only kept up to date for the ends of the fragments
+		let mut tails = (0..self.tsp_data.n).collect::<Vec<TSPNodeID>>();
+
+		// The new tour that results from this function
+		let mut new_tour = BBTour::new();
+
+		// Collect the good edges, each of them only once, sorted by length
+		let mut edges = Vec::new();
+		for (node_id, other_nodes) in &self.sparse_edge_map
+		{
+			for other_node_id in other_nodes.iter().filter(|other_node_id| node_id < *other_node_id)
+			{
+				edges.push(BBEdge::new(self.tsp_data, *node_id, *other_node_id));
+			}
+		}
+		edges.sort_by(|a, b| a.weight.partial_cmp(&b.weight).unwrap().then((a.start, a.end).cmp(&(b.start, b.end))));
+
+		for edge in &edges
+		{
+			self.add_greedy_edge(&mut degree, &mut tails, &mut new_tour, edge.start, edge.end);
+		}
+
+		// Make local copy of the KD tree as it gets edited, containing only
+		// the nodes that are ends of fragments
+		let mut local_kd_tree = self.kd_tree.as_ref().unwrap().clone();
+
+		// Join the fragments in rounds: Find for each end of a fragment the
+		// nearest end of another fragment and add these edges greedily
+		let mut disabled = vec![false; self.tsp_data.n];
+		while new_tour.edges.len() + 1 < self.tsp_data.n
+		{
+			let mut candidates = Vec::new();
+
+			for node in &self.tsp_data.nodes
+			{
+				if degree[node.id] >= 2
+				{
+					if !disabled[node.id]
+					{
+						local_kd_tree.disable_node(node);
+						disabled[node.id] = true;
+					}
+					continue;
+				}
+
+				// The other end of the own fragment is not a candidate
+				let tail_node = self.tsp_data.get_node(tails[node.id]);
+				if tail_node.id != node.id
+				{
+					local_kd_tree.disable_node(tail_node);
+				}
+
+				if let Some((nearest_neighbor, _)) = local_kd_tree.nearests(node, 1, self.tsp_data).first()
+				{
+					candidates.push(BBEdge::new(self.tsp_data, node.id, nearest_neighbor.id));
+				}
+
+				if tail_node.id != node.id
+				{
+					local_kd_tree.enable_node(tail_node);
+				}
+			}
+
+			candidates.sort_by(|a, b| a.weight.partial_cmp(&b.weight).unwrap());
+
+			for edge in &candidates
+			{
+				if new_tour.edges.len() + 1 >= self.tsp_data.n
+				{
+					break;
+				}
+				self.add_greedy_edge(&mut degree, &mut tails, &mut new_tour, edge.start, edge.end);
+			}
+		}
+
+		// Add the final edge between the two ends of the single fragment that
+		// is left to complete the tour
+		let lonely_nodes = self.tsp_data.nodes.iter()
+			.filter(|node| degree[node.id] < 2)
+			.map(|node| node.id)
+			.collect::<Vec<TSPNodeID>>();
+
+		if lonely_nodes.len() == 2
+		{
+			new_tour.add(BBEdge::new(self.tsp_data, lonely_nodes[0], lonely_nodes[1]));
+		}
+
+		// Place new tour in BBData struct
+		self.current_tour = Some(new_tour);
+	}
+
+	/// Adds the edge between the two nodes to the tour if both nodes have 
+	/// degree less than 2 and they are not the two ends of the same fragment
+	/// Updates the degrees and tails accordingly
+	fn
+	add_greedy_edge
+	(
+		&self,
+		degree:                        &mut [usize],
+		tails:                         &mut [TSPNodeID],
+		new_tour:                      &mut BBTour,
+		node1:                         TSPNodeID,
+		node2:                         TSPNodeID,
+	)
+	{
+		if degree[node1] >= 2 || degree[node2] >= 2 || tails[node1] == node2
+		{
+			return;
+		}
+
+		degree[node1] += 1;
+		degree[node2] += 1;
+
+		// The two fragments get joined, so their outer ends become the tails
+		// of each other
+		let tail_1 = tails[node1];
+		let tail_2 = tails[node2];
+		tails[tail_1] = tail_2;
+		tails[tail_2] = tail_1;
+
+		new_tour.add(BBEdge::new(self.tsp_data, node1, node2));
+	}
+}
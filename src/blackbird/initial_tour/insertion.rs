@@ -0,0 +1,445 @@
+use std::cmp::Ordering;
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+use rand::Rng;
+use rand::seq::SliceRandom;
+
+use crate::blackbird::data::bb_data::BBData;
+use crate::blackbird::data::bb_edge::BBEdge;
+use crate::blackbird::data::bb_tour::BBTour;
+use crate::blackbird::kd::kd_tree::KDtree;
+use crate::tsp_lib::node::TSPNodeID;
+use crate::tsp_lib::node::TSPWeight;
+
+/// The number of nearest tour nodes whose tour edges are considered when
+/// searching for the cheapest position to insert a node at
+/// This keeps the insertion heuristics fast, but the position found is not
+/// guaranteed to be the cheapest one of the whole partial tour
+const INSERTION_CANDIDATES: usize = 8;
+
+/// The partial tour of the insertion heuristics, stored as doubly linked list
+/// together with a KD tree in which only the nodes of the tour are enabled
+struct
+BBInsertionTour
+{
+	next:                              Vec<TSPNodeID>,
+	prev:                              Vec<TSPNodeID>,
+	in_tour:                           Vec<bool>,
+	size:                              usize,
+	kd_tree:                           KDtree,
+}
+
+/// An entry of the priority queues used by the insertion heuristics, ordered
+/// by its weight (and the node ids to break ties)
+struct
+BBInsertionEntry
+{
+	weight:                            TSPWeight,
+	node:                              TSPNodeID,
+	other:                             TSPNodeID,
+}
+
+impl
+PartialEq
+for
+BBInsertionEntry
+{
+	fn
+	eq
+	(
+		&self,
+		other: &Self
+	)
+	-> bool
+	{
+		self.cmp(other) == Ordering::Equal
+	}
+}
+
+impl Eq for BBInsertionEntry {}
+
+impl
+PartialOrd
+for
+BBInsertionEntry
+{
+	fn
+	partial_cmp
+	(
+		&self,
+		other: &Self
+	)
+	-> Option<Ordering>
+	{
+		Some(self.cmp(other))
+	}
+}
+
+impl
+Ord
+for
+BBInsertionEntry
+{
+	fn
+	cmp
+	(
+		&self,
+		other: &Self
+	)
+	-> Ordering
+	{
+		self.weight.total_cmp(&other.weight)
+			.then(self.node.cmp(&other.node))
+			.then(self.other.cmp(&other.other))
+	}
+}
+
+impl
+BBData<'_>
+{
+	/// Implements the nearest insertion tour creation algorithm, which always
+	/// inserts the node that is nearest to the partial tour
+	/// For this, a priority queue holds for each tour node its nearest node
+	/// outside of the tour, which gets updated once that node is in the tour
+	pub fn
+	create_initial_tour_nearest_insertion
+	(
+		&mut self
+	)
+	{
+		let (mut tour, first) = self.start_insertion_tour();
+
+		// Make local copy of the KD tree, containing only the nodes that are
+		// not in the tour yet
+		let mut outside_kd_tree = self.kd_tree.as_ref().unwrap().clone();
+		outside_kd_tree.disable_node(self.tsp_data.get_node(first));
+
+		let mut queue = BinaryHeap::new();
+		self.push_nearest_outside(&outside_kd_tree, &mut queue, first);
+
+		while let Some(Reverse(entry)) = queue.pop()
+		{
+			// Finish the tour quickly once the time limit is reached
+			if self.time_limit_reached()
+			{
+				break;
+			}
+
+			// The nearest node of this tour node has been inserted in the
+			// meantime, so the next nearest one needs to be found
+			if tour.in_tour[entry.other]
+			{
+				self.push_nearest_outside(&outside_kd_tree, &mut queue, entry.node);
+				continue;
+			}
+
+			let (_, after) = self.insertion_position(&tour, entry.other);
+			self.insert_node(&mut tour, entry.other, after);
+			outside_kd_tree.disable_node(self.tsp_data.get_node(entry.other));
+
+			self.push_nearest_outside(&outside_kd_tree, &mut queue, entry.node);
+			self.push_nearest_outside(&outside_kd_tree, &mut queue, entry.other);
+		}
+
+		self.current_tour = Some(self.finish_insertion_tour(&mut tour, first));
+	}
+
+	/// Implements the farthest insertion tour creation algorithm, which always
+	/// inserts the node whose distance to the partial tour is the largest
+	/// As this distance only decreases over time, the priority queue holds
+	/// upper bounds for it, which only get updated (via a nearest neighbor
+	/// search in the KD tree of the tour) when they are on top of the queue
+	pub fn
+	create_initial_tour_farthest_insertion
+	(
+		&mut self
+	)
+	{
+		let (mut tour, first) = self.start_insertion_tour();
+
+		let mut queue = self.tsp_data.nodes.iter()
+			.filter(|node| node.id != first)
+			.map(|node| BBInsertionEntry
+			{
+				weight: self.tsp_data.get_distance_between_via_id(first, node.id),
+				node:   node.id,
+				other:  first,
+			})
+			.collect::<BinaryHeap<BBInsertionEntry>>();
+
+		while let Some(entry) = queue.pop()
+		{
+			// Finish the tour quickly once the time limit is reached
+			if self.time_limit_reached()
+			{
+				break;
+			}
+
+			let node = self.tsp_data.get_node(entry.node);
+			let (nearest_tour_node, distance) = tour.kd_tree.nearests(node, 1, self.tsp_data)[0];
+
+			// The upper bound is not tight anymore, so try again later
+			if distance < entry.weight
+			{
+				queue.push(BBInsertionEntry { weight: distance, node: entry.node, other: nearest_tour_node.id });
+				continue;
+			}
+
+			let (_, after) = self.insertion_position(&tour, entry.node);
+			self.insert_node(&mut tour, entry.node, after);
+		}
+
+		self.current_tour = Some(self.finish_insertion_tour(&mut tour, first));
+	}
+
+	/// Implements the cheapest insertion tour creation algorithm, which always
+	/// inserts the node that increases the length of the partial tour the
+	/// least. The priority queue holds for each node outside of the tour its
+	/// cheapest insertion position, which gets recomputed if the tour edge at
+	/// this position is gone. After an insertion, the nodes nearest to the
+	/// inserted node get updated as the new tour edges may be cheaper for them
+	pub fn
+	create_initial_tour_cheapest_insertion
+	(
+		&mut self
+	)
+	{
+		let (mut tour, first) = self.start_insertion_tour();
+
+		// Make local copy of the KD tree, containing only the nodes that are
+		// not in the tour yet
+		let mut outside_kd_tree = self.kd_tree.as_ref().unwrap().clone();
+		outside_kd_tree.disable_node(self.tsp_data.get_node(first));
+
+		// The successor of the insertion position at the time the entry was
+		// created, to detect whether this tour edge still exists
+		let mut successors = vec![first; self.tsp_data.n];
+
+		let mut queue = self.tsp_data.nodes.iter()
+			.filter(|node| node.id != first)
+			.map(|node| Reverse(BBInsertionEntry
+			{
+				weight: 2.0 * self.tsp_data.get_distance_between_via_id(first, node.id),
+				node:   node.id,
+				other:  first,
+			}))
+			.collect::<BinaryHeap<Reverse<BBInsertionEntry>>>();
+
+		while let Some(Reverse(entry)) = queue.pop()
+		{
+			// Finish the tour quickly once the time limit is reached
+			if self.time_limit_reached()
+			{
+				break;
+			}
+
+			if tour.in_tour[entry.node]
+			{
+				continue;
+			}
+
+			if tour.next[entry.other] != successors[entry.node]
+			{
+				let (cost, after) = self.insertion_position(&tour, entry.node);
+				successors[entry.node] = tour.next[after];
+				queue.push(Reverse(BBInsertionEntry { weight: cost, node: entry.node, other: after }));
+				continue;
+			}
+
+			self.insert_node(&mut tour, entry.node, entry.other);
+			let node = self.tsp_data.get_node(entry.node);
+			outside_kd_tree.disable_node(node);
+
+			for (nearest_neighbor, _) in outside_kd_tree.nearests(node, INSERTION_CANDIDATES, self.tsp_data)
+			{
+				let (cost, after) = self.insertion_position(&tour, nearest_neighbor.id);
+				successors[nearest_neighbor.id] = tour.next[after];
+				queue.push(Reverse(BBInsertionEntry { weight: cost, node: nearest_neighbor.id, other: after }));
+			}
+		}
+
+		self.current_tour = Some(self.finish_insertion_tour(&mut tour, first));
+	}
+
+	/// Implements the random insertion tour creation algorithm, which inserts
+	/// the nodes in random order, each at its cheapest position
+	pub fn
+	create_initial_tour_random_insertion
+	(
+		&mut self
+	)
+	{
+		let (mut tour, first) = self.start_insertion_tour();
+
+		let mut local_node_ids = self.tsp_data.nodes.iter()
+			.map(|node| node.id)
+			.filter(|node_id| *node_id != first)
+			.collect::<Vec<TSPNodeID>>();
+		local_node_ids.shuffle(&mut self.random_generator);
+
+		for node_id in local_node_ids
+		{
+			// Finish the tour quickly once the time limit is reached
+			if self.time_limit_reached()
+			{
+				break;
+			}
+
+			let (_, after) = self.insertion_position(&tour, node_id);
+			self.insert_node(&mut tour, node_id, after);
+		}
+
+		self.current_tour = Some(self.finish_insertion_tour(&mut tour, first));
+	}
+
+	/// Creates the partial tour consisting only of a random node, which gets
+	/// returned as well
+	fn
+	start_insertion_tour
+	(
+		&mut self
+	)
+	-> (BBInsertionTour, TSPNodeID)
+	{
+		// Calling this is "safe" as the tree only gets constructed if it
+		// doesn't exist yet
+		self.construct_kd_tree();
+
+		// Make local copy of the KD tree, in which the nodes get enabled once
+		// they are part of the tour
+		let mut kd_tree = self.kd_tree.as_ref().unwrap().clone();
+		for node in &self.tsp_data.nodes
+		{
+			kd_tree.disable_node(node);
+		}
+
+		let first = self.random_generator.gen_range(0..self.tsp_data.n);
+		kd_tree.enable_node(self.tsp_data.get_node(first));
+
+		let mut tour = BBInsertionTour
+		{
+			next:                      vec![first; self.tsp_data.n],
+			prev:                      vec![first; self.tsp_data.n],
+			in_tour:                   vec![false; self.tsp_data.n],
+			size:                      1,
+			kd_tree:                   kd_tree,
+		};
+		tour.in_tour[first] = true;
+
+		return (tour, first);
+	}
+
+	/// Finds the cheapest position to insert the given node into the partial
+	/// tour, considering only the tour edges at the tour nodes nearest to it
+	/// (so this is an approximation, see INSERTION_CANDIDATES)
+	/// Returns the increase of the tour length and the tour node after which
+	/// the node should be inserted
+	fn
+	insertion_position
+	(
+		&self,
+		tour:                          &BBInsertionTour,
+		node_id:                       TSPNodeID,
+	)
+	-> (TSPWeight, TSPNodeID)
+	{
+		let node = self.tsp_data.get_node(node_id);
+		let mut best: Option<(TSPWeight, TSPNodeID)> = None;
+
+		for (tour_node, _) in tour.kd_tree.nearests(node, INSERTION_CANDIDATES, self.tsp_data)
+		{
+			for after in [tour.prev[tour_node.id], tour_node.id]
+			{
+				let before = tour.next[after];
+				let cost = self.tsp_data.get_distance_between_via_id(after, node_id)
+				         + self.tsp_data.get_distance_between_via_id(node_id, before)
+				         - self.tsp_data.get_distance_between_via_id(after, before);
+
+				if best.is_none() || cost < best.unwrap().0
+				{
+					best = Some((cost, after));
+				}
+			}
+		}
+
+		return best.unwrap();
+	}
+
+	/// Inserts the given node into the partial tour right after the given
+	/// tour node
+	fn
+	insert_node
+	(
+		&self,
+		tour:                          &mut BBInsertionTour,
+		node_id:                       TSPNodeID,
+		after:                         TSPNodeID,
+	)
+	{
+		let before = tour.next[after];
+		tour.next[after]     = node_id;
+		tour.prev[node_id]   = after;
+		tour.next[node_id]   = before;
+		tour.prev[before]    = node_id;
+		tour.in_tour[node_id] = true;
+		tour.size += 1;
+		tour.kd_tree.enable_node(self.tsp_data.get_node(node_id));
+	}
+
+	/// Pushes the nearest node outside of the tour for the given tour node
+	/// onto the priority queue of the nearest insertion, if there is one left
+	fn
+	push_nearest_outside
+	(
+		&self,
+		outside_kd_tree:               &KDtree,
+		queue:                         &mut BinaryHeap<Reverse<BBInsertionEntry>>,
+		node_id:                       TSPNodeID,
+	)
+	{
+		let node = self.tsp_data.get_node(node_id);
+		if let Some((nearest_neighbor, distance)) = outside_kd_tree.nearests(node, 1, self.tsp_data).first()
+		{
+			queue.push(Reverse(BBInsertionEntry { weight: *distance, node: node_id, other: nearest_neighbor.id }));
+		}
+	}
+
+	/// Converts the finished partial tour into a BBTour
+	/// If the insertion has been stopped due to the time limit, the remaining
+	/// nodes get inserted one after another before the first node
+	fn
+	finish_insertion_tour
+	(
+		&self,
+		tour:                          &mut BBInsertionTour,
+		first:                         TSPNodeID,
+	)
+	-> BBTour
+	{
+		for node_id in 0..self.tsp_data.n
+		{
+			if !tour.in_tour[node_id]
+			{
+				self.insert_node(tour, node_id, tour.prev[first]);
+			}
+		}
+		assert!(tour.size == self.tsp_data.n);
+
+		// For two nodes, this results in both edges between them, just like
+		// for the other tour construction methods
+		let mut new_tour = BBTour::new();
+		let mut current = 0;
+		loop
+		{
+			new_tour.add(BBEdge::new(self.tsp_data, current, tour.next[current]));
+			current = tour.next[current];
+			if current == 0
+			{
+				break;
+			}
+		}
+
+		return new_tour;
+	}
+}
@@ -0,0 +1,669 @@
+use std::collections::VecDeque;
+
+/// An edge of the graph of the matching, which for a blossom stores the
+/// original edge between two of the nodes the blossom consists of
+#[derive(Clone, Copy)]
+struct
+BBMatchingEdge
+{
+	u:                                 usize,
+	v:                                 usize,
+	w:                                 i64,
+}
+
+/// Computes a maximum weight matching in a complete graph with integer edge
+/// weights using Edmonds' blossom algorithm with dual variables in O(n^3)
+/// Nodes are numbered starting at 1, as 0 marks a missing node. The numbers
+/// above n are used for the blossoms, which get contracted into a single node
+pub struct
+BBBlossomMatching
+{
+	n:                                 usize,
+	n_x:                               usize,
+	g:                                 Vec<Vec<BBMatchingEdge>>,
+	lab:                               Vec<i64>,
+	mate:                              Vec<usize>,
+	slack:                             Vec<usize>,
+	st:                                Vec<usize>,
+	pa:                                Vec<usize>,
+	flower_from:                       Vec<Vec<usize>>,
+	s:                                 Vec<i32>,
+	vis:                               Vec<usize>,
+	flower:                            Vec<Vec<usize>>,
+	queue:                             VecDeque<usize>,
+	lca_stamp:                         usize,
+}
+
+impl
+BBBlossomMatching
+{
+	/// Creates the matching problem for n nodes without any edges
+	pub fn
+	new
+	(
+		n:                             usize,
+	)
+	-> BBBlossomMatching
+	{
+		let size = 2 * n + 1;
+
+		BBBlossomMatching
+		{
+			n:                         n,
+			n_x:                       n,
+			g:                         (0..size).map(|u| (0..size).map(|v| BBMatchingEdge { u: u, v: v, w: 0 }).collect()).collect(),
+			lab:                       vec![0; size],
+			mate:                      vec![0; size],
+			slack:                     vec![0; size],
+			st:                        vec![0; size],
+			pa:                        vec![0; size],
+			flower_from:               vec![vec![0; n + 1]; size],
+			s:                         vec![0; size],
+			vis:                       vec![0; size],
+			flower:                    vec![Vec::new(); size],
+			queue:                     VecDeque::new(),
+			lca_stamp:                 0,
+		}
+	}
+
+	/// Sets the weight of the edge between the nodes u and v (starting at 1)
+	pub fn
+	set_weight
+	(
+		&mut self,
+		u:                             usize,
+		v:                             usize,
+		w:                             i64,
+	)
+	{
+		self.g[u][v].w = w;
+		self.g[v][u].w = w;
+	}
+
+	/// Computes the matching and returns the partner of each node, with 0
+	/// for unmatched nodes
+	pub fn
+	solve
+	(
+		&mut self
+	)
+	-> Vec<usize>
+	{
+		let n = self.n;
+		self.n_x = n;
+		for u in 0..=n
+		{
+			self.st[u] = u;
+			self.flower[u].clear();
+		}
+
+		let mut w_max = 0;
+		for u in 1..=n
+		{
+			for v in 1..=n
+			{
+				self.flower_from[u][v] = if u == v { u } else { 0 };
+				w_max = w_max.max(self.g[u][v].w);
+			}
+		}
+		for u in 1..=n
+		{
+			self.lab[u] = w_max;
+		}
+
+		while self.augmenting_path() {}
+
+		return self.mate[..=n].to_vec();
+	}
+
+	fn
+	e_delta
+	(
+		&self,
+		e:                             BBMatchingEdge,
+	)
+	-> i64
+	{
+		return self.lab[e.u] + self.lab[e.v] - self.g[e.u][e.v].w * 2;
+	}
+
+	fn
+	update_slack
+	(
+		&mut self,
+		u:                             usize,
+		x:                             usize,
+	)
+	{
+		if self.slack[x] == 0 || self.e_delta(self.g[u][x]) < self.e_delta(self.g[self.slack[x]][x])
+		{
+			self.slack[x] = u;
+		}
+	}
+
+	fn
+	set_slack
+	(
+		&mut self,
+		x:                             usize,
+	)
+	{
+		self.slack[x] = 0;
+		for u in 1..=self.n
+		{
+			if self.g[u][x].w > 0 && self.st[u] != x && self.s[self.st[u]] == 0
+			{
+				self.update_slack(u, x);
+			}
+		}
+	}
+
+	fn
+	queue_push
+	(
+		&mut self,
+		x:                             usize,
+	)
+	{
+		if x <= self.n
+		{
+			self.queue.push_back(x);
+		}
+		else
+		{
+			for i in 0..self.flower[x].len()
+			{
+				self.queue_push(self.flower[x][i]);
+			}
+		}
+	}
+
+	fn
+	set_st
+	(
+		&mut self,
+		x:                             usize,
+		b:                             usize,
+	)
+	{
+		self.st[x] = b;
+		if x > self.n
+		{
+			for i in 0..self.flower[x].len()
+			{
+				self.set_st(self.flower[x][i], b);
+			}
+		}
+	}
+
+	fn
+	get_pr
+	(
+		&mut self,
+		b:                             usize,
+		xr:                            usize,
+	)
+	-> usize
+	{
+		let pr = self.flower[b].iter().position(|x| *x == xr).unwrap();
+		if pr % 2 == 1
+		{
+			self.flower[b][1..].reverse();
+			return self.flower[b].len() - pr;
+		}
+		return pr;
+	}
+
+	fn
+	set_match
+	(
+		&mut self,
+		u:                             usize,
+		v:                             usize,
+	)
+	{
+		self.mate[u] = self.g[u][v].v;
+		if u <= self.n
+		{
+			return;
+		}
+
+		let e = self.g[u][v];
+		let xr = self.flower_from[u][e.u];
+		let pr = self.get_pr(u, xr);
+		for i in 0..pr
+		{
+			self.set_match(self.flower[u][i], self.flower[u][i ^ 1]);
+		}
+		self.set_match(xr, v);
+		self.flower[u].rotate_left(pr);
+	}
+
+	fn
+	augment
+	(
+		&mut self,
+		mut u:                         usize,
+		mut v:                         usize,
+	)
+	{
+		loop
+		{
+			let xnv = self.st[self.mate[u]];
+			self.set_match(u, v);
+			if xnv == 0
+			{
+				return;
+			}
+			self.set_match(xnv, self.st[self.pa[xnv]]);
+			u = self.st[self.pa[xnv]];
+			v = xnv;
+		}
+	}
+
+	fn
+	get_lca
+	(
+		&mut self,
+		mut u:                         usize,
+		mut v:                         usize,
+	)
+	-> usize
+	{
+		self.lca_stamp += 1;
+		while u != 0 || v != 0
+		{
+			if u != 0
+			{
+				if self.vis[u] == self.lca_stamp
+				{
+					return u;
+				}
+				self.vis[u] = self.lca_stamp;
+				u = self.st[self.mate[u]];
+				if u != 0
+				{
+					u = self.st[self.pa[u]];
+				}
+			}
+			std::mem::swap(&mut u, &mut v);
+		}
+		return 0;
+	}
+
+	fn
+	add_blossom
+	(
+		&mut self,
+		u:                             usize,
+		lca:                           usize,
+		v:                             usize,
+	)
+	{
+		let mut b = self.n + 1;
+		while b <= self.n_x && self.st[b] != 0
+		{
+			b += 1;
+		}
+		if b > self.n_x
+		{
+			self.n_x += 1;
+		}
+
+		self.lab[b] = 0;
+		self.s[b] = 0;
+		self.mate[b] = self.mate[lca];
+		self.flower[b].clear();
+		self.flower[b].push(lca);
+
+		let mut x = u;
+		while x != lca
+		{
+			let y = self.st[self.mate[x]];
+			self.flower[b].push(x);
+			self.flower[b].push(y);
+			self.queue_push(y);
+			x = self.st[self.pa[y]];
+		}
+		self.flower[b][1..].reverse();
+
+		let mut x = v;
+		while x != lca
+		{
+			let y = self.st[self.mate[x]];
+			self.flower[b].push(x);
+			self.flower[b].push(y);
+			self.queue_push(y);
+			x = self.st[self.pa[y]];
+		}
+
+		self.set_st(b, b);
+		for x in 1..=self.n_x
+		{
+			self.g[b][x].w = 0;
+			self.g[x][b].w = 0;
+		}
+		for x in 1..=self.n
+		{
+			self.flower_from[b][x] = 0;
+		}
+
+		for i in 0..self.flower[b].len()
+		{
+			let xs = self.flower[b][i];
+			for x in 1..=self.n_x
+			{
+				if self.g[b][x].w == 0 || self.e_delta(self.g[xs][x]) < self.e_delta(self.g[b][x])
+				{
+					self.g[b][x] = self.g[xs][x];
+					self.g[x][b] = self.g[x][xs];
+				}
+			}
+			for x in 1..=self.n
+			{
+				if self.flower_from[xs][x] != 0
+				{
+					self.flower_from[b][x] = xs;
+				}
+			}
+		}
+
+		self.set_slack(b);
+	}
+
+	fn
+	expand_blossom
+	(
+		&mut self,
+		b:                             usize,
+	)
+	{
+		for i in 0..self.flower[b].len()
+		{
+			self.set_st(self.flower[b][i], self.flower[b][i]);
+		}
+
+		let xr = self.flower_from[b][self.g[b][self.pa[b]].u];
+		let pr = self.get_pr(b, xr);
+
+		let mut i = 0;
+		while i < pr
+		{
+			let xs = self.flower[b][i];
+			let xns = self.flower[b][i + 1];
+			self.pa[xs] = self.g[xns][xs].u;
+			self.s[xs] = 1;
+			self.s[xns] = 0;
+			self.slack[xs] = 0;
+			self.set_slack(xns);
+			self.queue_push(xns);
+			i += 2;
+		}
+
+		self.s[xr] = 1;
+		self.pa[xr] = self.pa[b];
+
+		for i in pr + 1..self.flower[b].len()
+		{
+			let xs = self.flower[b][i];
+			self.s[xs] = -1;
+			self.set_slack(xs);
+		}
+
+		self.st[b] = 0;
+	}
+
+	fn
+	on_found_edge
+	(
+		&mut self,
+		e:                             BBMatchingEdge,
+	)
+	-> bool
+	{
+		let u = self.st[e.u];
+		let v = self.st[e.v];
+
+		if self.s[v] == -1
+		{
+			self.pa[v] = e.u;
+			self.s[v] = 1;
+			let nu = self.st[self.mate[v]];
+			self.slack[v] = 0;
+			self.slack[nu] = 0;
+			self.s[nu] = 0;
+			self.queue_push(nu);
+		}
+		else if self.s[v] == 0
+		{
+			let lca = self.get_lca(u, v);
+			if lca == 0
+			{
+				self.augment(u, v);
+				self.augment(v, u);
+				return true;
+			}
+			self.add_blossom(u, lca, v);
+		}
+
+		return false;
+	}
+
+	/// Searches for an augmenting path, adjusting the dual variables until one
+	/// is found. Returns false if the matching can not be increased anymore
+	fn
+	augmenting_path
+	(
+		&mut self
+	)
+	-> bool
+	{
+		for x in 1..=self.n_x
+		{
+			self.s[x] = -1;
+			self.slack[x] = 0;
+		}
+		self.queue.clear();
+
+		for x in 1..=self.n_x
+		{
+			if self.st[x] == x && self.mate[x] == 0
+			{
+				self.pa[x] = 0;
+				self.s[x] = 0;
+				self.queue_push(x);
+			}
+		}
+		if self.queue.is_empty()
+		{
+			return false;
+		}
+
+		loop
+		{
+			while let Some(u) = self.queue.pop_front()
+			{
+				if self.s[self.st[u]] == 1
+				{
+					continue;
+				}
+
+				for v in 1..=self.n
+				{
+					if self.g[u][v].w > 0 && self.st[u] != self.st[v]
+					{
+						if self.e_delta(self.g[u][v]) == 0
+						{
+							if self.on_found_edge(self.g[u][v])
+							{
+								return true;
+							}
+						}
+						else
+						{
+							self.update_slack(u, self.st[v]);
+						}
+					}
+				}
+			}
+
+			let mut d = i64::MAX;
+			for b in self.n + 1..=self.n_x
+			{
+				if self.st[b] == b && self.s[b] == 1
+				{
+					d = d.min(self.lab[b] / 2);
+				}
+			}
+			for x in 1..=self.n_x
+			{
+				if self.st[x] == x && self.slack[x] != 0
+				{
+					if self.s[x] == -1
+					{
+						d = d.min(self.e_delta(self.g[self.slack[x]][x]));
+					}
+					else if self.s[x] == 0
+					{
+						d = d.min(self.e_delta(self.g[self.slack[x]][x]) / 2);
+					}
+				}
+			}
+
+			// The matching is maximum once the dual variable of an even node
+			// would drop to zero. This needs to be checked before the update,
+			// as d might not be bounded by anything else
+			for u in 1..=self.n
+			{
+				if self.s[self.st[u]] == 0 && self.lab[u] <= d
+				{
+					return false;
+				}
+			}
+
+			for u in 1..=self.n
+			{
+				if self.s[self.st[u]] == 0
+				{
+					self.lab[u] -= d;
+				}
+				else if self.s[self.st[u]] == 1
+				{
+					self.lab[u] += d;
+				}
+			}
+			for b in self.n + 1..=self.n_x
+			{
+				if self.st[b] == b
+				{
+					if self.s[b] == 0
+					{
+						self.lab[b] += d * 2;
+					}
+					else if self.s[b] == 1
+					{
+						self.lab[b] -= d * 2;
+					}
+				}
+			}
+
+			self.queue.clear();
+			for x in 1..=self.n_x
+			{
+				if self.st[x] == x && self.slack[x] != 0 && self.st[self.slack[x]] != x
+				&& self.e_delta(self.g[self.slack[x]][x]) == 0
+				&& self.on_found_edge(self.g[self.slack[x]][x])
+				{
+					return true;
+				}
+			}
+			for b in self.n + 1..=self.n_x
+			{
+				if self.st[b] == b && self.s[b] == 1 && self.lab[b] == 0
+				{
+					self.expand_blossom(b);
+				}
+			}
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests
+{
+	use rand::Rng;
+	use rand::rngs::StdRng;
+
+	use super::*;
+
+	/// Computes the weight of a maximum weight matching by trying all of them
+	/// via dynamic programming over the subsets of the nodes (starting at 0)
+	fn
+	brute_force_weight
+	(
+		weights:                       &[Vec<i64>],
+	)
+	-> i64
+	{
+		let n = weights.len();
+		let mut best = vec![0; 1 << n];
+
+		for subset in 1..(1usize << n)
+		{
+			// The lowest node of the subset is either unmatched or matched to
+			// any other node of the subset
+			let u = subset.trailing_zeros() as usize;
+			let rest = subset & !(1 << u);
+			best[subset] = best[rest];
+
+			for v in (u+1..n).filter(|v| rest & (1 << v) != 0)
+			{
+				best[subset] = best[subset].max(weights[u][v] + best[rest & !(1 << v)]);
+			}
+		}
+
+		return best[(1 << n) - 1];
+	}
+
+	#[test]
+	fn
+	matches_brute_force
+	()
+	{
+		let mut random_generator: StdRng = rand_seeder::Seeder::from(42).make_rng();
+
+		for _ in 0..500
+		{
+			let n = random_generator.gen_range(1..=12);
+			let mut weights = vec![vec![0; n]; n];
+			let mut matching = BBBlossomMatching::new(n);
+
+			// Small weights lead to many ties, and zero weights to unmatched
+			// nodes, which both are corner cases of the blossom algorithm
+			let weight_max = if random_generator.gen_bool(0.5) { 5 } else { 1000 };
+			for (u, v) in (0..n).flat_map(|u| (u+1..n).map(move |v| (u, v)))
+			{
+				weights[u][v] = random_generator.gen_range(0..=weight_max);
+				weights[v][u] = weights[u][v];
+				matching.set_weight(u + 1, v + 1, weights[u][v]);
+			}
+
+			let mates = matching.solve();
+			let mut weight = 0;
+			for u in 1..=n
+			{
+				if mates[u] != 0
+				{
+					assert_eq!(mates[mates[u]], u);
+					assert_ne!(mates[u], u);
+					if mates[u] > u
+					{
+						weight += weights[u - 1][mates[u] - 1];
+					}
+				}
+			}
+
+			assert_eq!(weight, brute_force_weight(&weights), "weights: {:?}", weights);
+		}
+	}
+}
@@ -6,6 +6,40 @@ pub enum EInitialTourMethod
 	Random,
 	Boruvka,
 	QuickBoruvka,
+
+	/// Adds the shortest good edges first, as long as they form paths
+	Greedy,
+
+	/// Always moves on to the nearest node not visited yet
+	NearestNeighbor,
+
+	/// Follows a Hilbert curve through the plane
+	SpaceFillingCurve,
+
+	/// Shortcuts an Euler tour of a minimum spanning tree together with a
+	/// perfect matching of its odd degree nodes
+	Christofides,
+
+	/// Inserts the node nearest to the partial tour at its cheapest position
+	/// Like for all insertion methods, only the tour edges at the nearest
+	/// tour nodes are candidates for this position, so it is approximate
+	NearestInsertion,
+
+	/// Inserts the node farthest from the partial tour at its (approximately)
+	/// cheapest position
+	FarthestInsertion,
+
+	/// Inserts the node that increases the length of the partial tour the
+	/// least, comparing the (approximately) cheapest positions of the nodes
+	CheapestInsertion,
+
+	/// Inserts the nodes in random order, each at its (approximately) 
+	/// cheapest position
+	RandomInsertion,
+
+	/// Reads the initial tour from the given TSPLIB TOUR file, e.g. to 
+	/// continue from a tour that has been found before or by another tool
+	FromFile(String),
 }
 
 impl
@@ -29,6 +63,14 @@ EInitialTourMethod
 			'r'                        => Ok(EInitialTourMethod::Random),
 			'b'                        => Ok(EInitialTourMethod::Boruvka),
 			'q'                        => Ok(EInitialTourMethod::QuickBoruvka),
+			'g'                        => Ok(EInitialTourMethod::Greedy),
+			'n'                        => Ok(EInitialTourMethod::NearestNeighbor),
+			'h'                        => Ok(EInitialTourMethod::SpaceFillingCurve),
+			'c'                        => Ok(EInitialTourMethod::Christofides),
+			'i'                        => Ok(EInitialTourMethod::NearestInsertion),
+			'f'                        => Ok(EInitialTourMethod::FarthestInsertion),
+			'p'                        => Ok(EInitialTourMethod::CheapestInsertion),
+			'a'                        => Ok(EInitialTourMethod::RandomInsertion),
 			_                          => Err(())
 		}
 	}
@@ -2,4 +2,11 @@ pub mod method;
 pub mod random;
 pub mod boruvka;
 pub mod quick_boruvka;
+pub mod greedy;
+pub mod nearest_neighbor;
+pub mod space_filling_curve;
+pub mod christofides;
+pub mod matching;
+pub mod insertion;
+pub mod from_file;
 pub mod create;
\ No newline at end of file
@@ -0,0 +1,60 @@
+use rand::Rng;
+
+use crate::blackbird::data::bb_data::BBData;
+use crate::blackbird::data::bb_edge::BBEdge;
+use crate::blackbird::data::bb_tour::BBTour;
+
+impl
+BBData<'_>
+{
+	/// Implements the nearest neighbor tour creation algorithm, which starts
+	/// at a random node and always moves on to the nearest node that has not
+	/// been visited yet. Visited nodes get disabled in a local copy of the KD
+	/// tree, so that each step is a single nearest neighbor search
+	/// In CONCORDE, this can be found as "CCkdtree_nearest_neighbor_tour" in
+	/// kdspan.c
+	pub fn
+	create_initial_tour_nearest_neighbor
+	(
+		&mut self
+	)
+	{
+		// Calling this is "safe" as the tree only gets constructed if it
+		// doesn't exist yet
+		self.construct_kd_tree();
+
+		// Make local copy of the KD tree as it gets edited
+		let mut local_kd_tree = self.kd_tree.as_ref().unwrap().clone();
+
+		let first = self.random_generator.gen_range(0..self.tsp_data.n);
+		let mut current = first;
+
+		// The new tour that results from this function
+		let mut new_tour = BBTour::new();
+
+		loop
+		{
+			let current_node = self.tsp_data.get_node(current);
+			local_kd_tree.disable_node(current_node);
+
+			// If no enabled node is left, all nodes have been visited
+			let Some((nearest_neighbor, _)) = local_kd_tree.nearests(current_node, 1, self.tsp_data).first().copied()
+			else
+			{
+				break;
+			};
+
+			new_tour.add(BBEdge::new(self.tsp_data, current, nearest_neighbor.id));
+			current = nearest_neighbor.id;
+		}
+
+		// Close the tour
+		if current != first
+		{
+			new_tour.add(BBEdge::new(self.tsp_data, current, first));
+		}
+
+		// Place new tour in BBData struct
+		self.current_tour = Some(new_tour);
+	}
+}
@@ -4,7 +4,7 @@ use crate::blackbird::data::bb_tour::BBTour;
 use crate::tsp_lib::node::*;
 
 impl
-BBData
+BBData<'_>
 {
 	/// Implements the Quick-Boruvka tour creation algorithm as described in
 	/// "The Traveling Salesman Problem: A Computational Study" by
@@ -12,6 +12,7 @@ BBData
 	/// - Robert E. Bixby
 	/// - Vašek Chvatál
 	/// - William J. Cook
+	///
 	/// ISBN: 9780691129938
 	/// Page 455f
 	/// In CONCORDE, this can be found as 
@@ -72,11 +73,11 @@ BBData
 						let tail_node = self.tsp_data.get_node(tails[node.id]);
 						local_kd_tree.disable_node(tail_node);
 
-						let nearest_neighbor_candidates = local_kd_tree.nearests(node, 1, &self.tsp_data);
+						let nearest_neighbor_candidates = local_kd_tree.nearests(node, 1, self.tsp_data);
 
 						// If no such node exists, we are done an only have one
 						// final edge left to add to close the tour
-						if nearest_neighbor_candidates.first().is_none()
+						if nearest_neighbor_candidates.is_empty()
 						{
 							break;
 						}
@@ -88,7 +89,7 @@ BBData
 					}
 					else
 					{
-						nearest_neighbor = local_kd_tree.nearests(node, 1, &self.tsp_data).first().unwrap().0;
+						nearest_neighbor = local_kd_tree.nearests(node, 1, self.tsp_data).first().unwrap().0;
 					}
 
 					// Remove nodes from the KD tree if they already have a
@@ -104,17 +105,17 @@ BBData
 					}
 
 					// Increase degrees
-					degree[node.id            ] = degree[node.id            ] + 1;
-					degree[nearest_neighbor.id] = degree[nearest_neighbor.id] + 1;
+					degree[node.id            ] += 1;
+					degree[nearest_neighbor.id] += 1;
 
 					// Insert edge
-					let new_edge = BBEdge::new(&self.tsp_data, node.id, nearest_neighbor.id);
+					let new_edge = BBEdge::new(self.tsp_data, node.id, nearest_neighbor.id);
 					new_tour.add(new_edge);
 
 					// Handle the tails
-					if !(tails[node.id] < TSPNodeID::MAX)
+					if (tails[node.id] == TSPNodeID::MAX)
 					{
-						if !(tails[nearest_neighbor.id] < TSPNodeID::MAX)
+						if (tails[nearest_neighbor.id] == TSPNodeID::MAX)
 						{
 							// Both don't have a tail yet
 							tails[node.id]             = nearest_neighbor.id;
@@ -128,7 +129,7 @@ BBData
 							tails[tail]    = node.id;
 						}
 					}
-					else if !(tails[nearest_neighbor.id] < TSPNodeID::MAX)
+					else if (tails[nearest_neighbor.id] == TSPNodeID::MAX)
 					{
 						// node does have a tail but its partner hasn't yet
 						let tail = tails[node.id];
@@ -152,7 +153,7 @@ BBData
 			.filter(|node| degree[node.id] > 2)
 			.collect::<Vec<&TSPNode>>();
 
-		assert!(bad_nodes.len() == 0);
+		assert!(bad_nodes.is_empty());
 
 		// Find the two nodes that haven't been connected yet via an edge
 		let lonely_nodes = self.tsp_data.nodes.iter()
@@ -163,7 +164,7 @@ BBData
 		assert!(lonely_nodes.len() == 2);
 
 		// Add the final edge to complete the tour
-		let new_edge = BBEdge::new(&self.tsp_data, lonely_nodes[0].id, lonely_nodes[1].id);
+		let new_edge = BBEdge::new(self.tsp_data, lonely_nodes[0].id, lonely_nodes[1].id);
 		new_tour.add(new_edge);
 
 		// Place new tour in BBData struct
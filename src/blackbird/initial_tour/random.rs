@@ -1,25 +1,41 @@
+use rand::rngs::StdRng;
 use rand::seq::SliceRandom;
 
 use crate::blackbird::data::bb_edge::BBEdge;
 use crate::blackbird::data::bb_tour::BBTour;
 
 use crate::blackbird::data::bb_data::BBData;
+use crate::tsp_lib::data::TSPData;
 use crate::tsp_lib::node::TSPNodeID;
 
 impl
-BBData
+BBData<'_>
 {
 	pub fn
 	create_initial_tour_random
 	(
 		&mut self
 	)
+	{
+		// Replace current with new tour
+		self.current_tour = Some(BBData::random_tour(self.tsp_data, &mut self.random_generator));
+	}
+
+	/// Creates a tour that visits the nodes in random order, using the given
+	/// random generator. This is also needed by the random restart kick
+	pub fn
+	random_tour
+	(
+		tsp_data:                      &TSPData,
+		random_generator:              &mut StdRng,
+	)
+	-> BBTour
 	{
 		// Collect the ids of all nodes
-		let mut local_node_ids = self.tsp_data.nodes.iter().map(|node| node.id).collect::<Vec<TSPNodeID>>();
+		let mut local_node_ids = tsp_data.nodes.iter().map(|node| node.id).collect::<Vec<TSPNodeID>>();
 
 		// Shuffle the ids
-		local_node_ids.shuffle(&mut self.random_generator);
+		local_node_ids.shuffle(random_generator);
 
 		// Initialize the new tour
 		let mut new_tour = BBTour::new();
@@ -28,13 +44,12 @@ BBData
 		for i in 0..local_node_ids.len()-1
 		{
 			// Add the new edge to the tour
-			new_tour.add(BBEdge::new(&self.tsp_data, local_node_ids[i], local_node_ids[i+1]));
+			new_tour.add(BBEdge::new(tsp_data, local_node_ids[i], local_node_ids[i+1]));
 		}
 
 		// Close the tour
-		new_tour.add(BBEdge::new(&self.tsp_data, local_node_ids[0], local_node_ids[local_node_ids.len()-1]));
+		new_tour.add(BBEdge::new(tsp_data, local_node_ids[0], local_node_ids[local_node_ids.len()-1]));
 
-		// Replace current with new tour
-		self.current_tour = Some(new_tour);
+		return new_tour;
 	}
 }
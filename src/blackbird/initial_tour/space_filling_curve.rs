@@ -0,0 +1,102 @@
+use crate::blackbird::data::bb_data::BBData;
+use crate::blackbird::data::bb_edge::BBEdge;
+use crate::blackbird::data::bb_tour::BBTour;
+use crate::tsp_lib::node::*;
+use crate::warn;
+
+/// The number of bits per coordinate of the grid on which the Hilbert curve
+/// gets evaluated
+const HILBERT_ORDER: u32 = 16;
+
+impl
+BBData<'_>
+{
+	/// Implements the space-filling curve tour creation algorithm, which
+	/// visits the nodes in the order in which a Hilbert curve passes through
+	/// them. The nodes are placed on a grid covering their bounding box, and
+	/// only the x and y coordinates are taken into account. As this is just
+	/// sorting by the position along the curve, it only requires O(n log n)
+	/// time and no KD tree
+	pub fn
+	create_initial_tour_space_filling_curve
+	(
+		&mut self
+	)
+	{
+		// Without coordinates, there is no curve to follow
+		if !self.tsp_data.distance.is_geometric()
+		{
+			warn!("The space-filling curve tour requires coordinates, using the nearest neighbor tour instead!");
+			self.create_initial_tour_nearest_neighbor();
+			return;
+		}
+
+		let nodes = &self.tsp_data.nodes;
+		let min_x = nodes.iter().map(|node| node.x).fold(TSPWeight::MAX, TSPWeight::min);
+		let max_x = nodes.iter().map(|node| node.x).fold(TSPWeight::MIN, TSPWeight::max);
+		let min_y = nodes.iter().map(|node| node.y).fold(TSPWeight::MAX, TSPWeight::min);
+		let max_y = nodes.iter().map(|node| node.y).fold(TSPWeight::MIN, TSPWeight::max);
+
+		// Use the same scale for both axes to keep the curve undistorted
+		let side = (1u64 << HILBERT_ORDER) - 1;
+		let scale = side as TSPWeight / (max_x - min_x).max(max_y - min_y).max(TSPWeight::MIN_POSITIVE);
+
+		let mut order = nodes.iter()
+			.map(|node|
+			{
+				let x = ((node.x - min_x) * scale) as u64;
+				let y = ((node.y - min_y) * scale) as u64;
+				(Self::hilbert_index(x.min(side), y.min(side)), node.id)
+			})
+			.collect::<Vec<(u64, TSPNodeID)>>();
+		order.sort_unstable();
+
+		// The new tour that results from this function
+		let mut new_tour = BBTour::new();
+
+		for i in 0..order.len()
+		{
+			new_tour.add(BBEdge::new(self.tsp_data, order[i].1, order[(i+1) % order.len()].1));
+		}
+
+		// Place new tour in BBData struct
+		self.current_tour = Some(new_tour);
+	}
+
+	/// Computes the position of a grid point along the Hilbert curve, based
+	/// on the well-known iterative algorithm that rotates the quadrants
+	fn
+	hilbert_index
+	(
+		mut x:                         u64,
+		mut y:                         u64,
+	)
+	-> u64
+	{
+		let n = 1u64 << HILBERT_ORDER;
+		let mut index = 0;
+
+		let mut s = n / 2;
+		while s > 0
+		{
+			let rx = (x & s > 0) as u64;
+			let ry = (y & s > 0) as u64;
+			index += s * s * ((3 * rx) ^ ry);
+
+			// Rotate the quadrant so that the curve in it has the base shape
+			if ry == 0
+			{
+				if rx == 1
+				{
+					x = n - 1 - x;
+					y = n - 1 - y;
+				}
+				std::mem::swap(&mut x, &mut y);
+			}
+
+			s /= 2;
+		}
+
+		return index;
+	}
+}
@@ -38,10 +38,8 @@ KDtreeNodeData
 	)
 	-> Ordering
 	{
-		match axis
-		{
-			E_SPLIT_AXIS::X => self.tsp_node.x.partial_cmp(&other.tsp_node.x).unwrap(),
-			E_SPLIT_AXIS::Y => self.tsp_node.y.partial_cmp(&other.tsp_node.y).unwrap(),
-		}
+		self.tsp_node.coordinate(axis.dimension()).partial_cmp(
+			&other.tsp_node.coordinate(axis.dimension())
+		).unwrap()
 	}
 }
\ No newline at end of file
@@ -21,6 +21,10 @@ KDtree
 	bucket:                            Option<Vec<KDtreeNodeData>>,
 	l_child:                           Option<Box<KDtree>>,
 	r_child:                           Option<Box<KDtree>>,
+
+	// The number of enabled nodes in this (sub-)tree, so that searches can
+	// skip subtrees in which all nodes are disabled
+	enabled_count:                     usize,
 }
 
 #[allow(non_camel_case_types)]
@@ -30,6 +34,45 @@ E_SPLIT_AXIS
 {
 	X,
 	Y,
+	Z,
+}
+
+impl
+E_SPLIT_AXIS
+{
+	/// Gets the axis for the given dimension, with 0, 1 and 2 corresponding 
+	/// to X, Y and Z
+	pub fn
+	from_dimension
+	(
+		dimension:                     usize
+	)
+	-> E_SPLIT_AXIS
+	{
+		match dimension
+		{
+			0 => E_SPLIT_AXIS::X,
+			1 => E_SPLIT_AXIS::Y,
+			2 => E_SPLIT_AXIS::Z,
+			_ => panic!("E_SPLIT_AXIS from_dimension: Invalid dimension {}!", dimension),
+		}
+	}
+
+	/// Gets the dimension this axis corresponds to
+	pub fn
+	dimension
+	(
+		&self
+	)
+	-> usize
+	{
+		match self
+		{
+			E_SPLIT_AXIS::X => 0,
+			E_SPLIT_AXIS::Y => 1,
+			E_SPLIT_AXIS::Z => 2,
+		}
+	}
 }
 
 impl
@@ -43,10 +86,14 @@ KDtree
 	/// This is construction is done recursively, splitting the data at each 
 	/// level. At some point, when there is not enough data (as defined by the
 	/// CUTOFF constant), a bucket is used instead of individual nodes. 
+	/// If the distances are not related to the coordinates of the nodes (e.g.
+	/// for explicitly given edge weights), splitting does not make any sense.
+	/// Instead, a single bucket containing all nodes is used, turning every 
+	/// query into a linear search over the nodes. 
 	pub fn
-	new<'a>
+	new
 	(
-		tsp_data:                      &'a TSPData,
+		tsp_data:                      &TSPData,
 		random_generator:              &mut StdRng,
 	)
 	-> KDtree
@@ -54,14 +101,27 @@ KDtree
 		// Construct the KD node data
 		let kd_nodes: Vec<_> = tsp_data.nodes
 			.iter()
-			.map(|tsp_node| KDtreeNodeData::new(tsp_node.clone()))
+			.map(|tsp_node| KDtreeNodeData::new(*tsp_node))
 			.collect();
+
+		// Non-geometric fallback
+		if !tsp_data.distance.is_geometric()
+		{
+			return KDtree 
+			{ 
+				root:                  None, 
+				cut_axis:              None,
+				l_child:               None,
+				r_child:               None,
+				enabled_count:         kd_nodes.len(),
+				bucket:                Some(kd_nodes),
+			}
+		}
 		
 		// Call the recursive construction method
 		let kd_tree = Self::recursive_new(
 			tsp_data,
 			&kd_nodes,
-			0,
 			random_generator
 		);
 
@@ -72,8 +132,7 @@ KDtree
 	recursive_new
 	(
 		tsp_data:                      &TSPData,
-		kd_nodes:                      &Vec<KDtreeNodeData>,
-		depth:                         usize,
+		kd_nodes:                      &[KDtreeNodeData],
 		random_generator:              &mut StdRng,
 	)
 	-> KDtree
@@ -88,14 +147,15 @@ KDtree
 				cut_axis:              None,
 				l_child:               None,
 				r_child:               None,
-				bucket:                Some(kd_nodes.clone()),
+				bucket:                Some(kd_nodes.to_vec()),
+				enabled_count:         kd_nodes.len(),
 			}
 		}
 		else // Too many data points to handle, need to further subdivide
 		{
 
 			// Determine cut axis and node
-			let axis = Self::determine_split_axis(kd_nodes);
+			let axis = Self::determine_split_axis(kd_nodes, tsp_data.distance.dimensions());
 
 			let cut_node = Self::determine_cut_node(
 				kd_nodes, 
@@ -110,8 +170,7 @@ KDtree
 					|kd_node| 
 					kd_node.axis_compare(&cut_node, axis) == Ordering::Less &&
 					kd_node.tsp_node.id != cut_node.tsp_node.id
-				)
-				.map(|node| node.clone())
+				).copied()
 				.collect();
 			let right_subset: Vec<_> = kd_nodes
 				.iter()
@@ -119,22 +178,19 @@ KDtree
 					|kd_node| 
 					kd_node.axis_compare(&cut_node, axis) != Ordering::Less &&
 					kd_node.tsp_node.id != cut_node.tsp_node.id
-				)
-				.map(|node| node.clone())
+				).copied()
 				.collect();
 
 			// Build the two subtrees that form the children
 			let left_child = Self::recursive_new(
 				tsp_data, 
 				&left_subset, 
-				depth+1,
 				random_generator
 			);
 
 			let right_child = Self::recursive_new(
 				tsp_data, 
 				&right_subset, 
-				depth+1,
 				random_generator
 			);
 
@@ -143,6 +199,7 @@ KDtree
 			{ 
 				root:                  Some(cut_node), 
 				cut_axis:              Some(axis),
+				enabled_count:         1 + left_child.enabled_count + right_child.enabled_count,
 				l_child:               Some(Box::new(left_child)), 
 				r_child:               Some(Box::new(right_child)), 
 				bucket:                None,
@@ -153,37 +210,38 @@ KDtree
 	}
 
 	/// Determines the axis along which the split needs to be performed for a
-	/// given set of data
+	/// given set of data, which is the one with the largest spread of the 
+	/// coordinates. In case of a tie, the latter axis is preferred. 
 	fn
 	determine_split_axis
 	(
-		data:                          &Vec<KDtreeNodeData>,
+		data:                          &[KDtreeNodeData],
+		dimensions:                    usize,
 	)
 	-> E_SPLIT_AXIS
 	{
-		let mut min_x = TSPWeight::MAX;
-		let mut min_y = TSPWeight::MAX;
-
-		let mut max_x = TSPWeight::MIN;
-		let mut max_y = TSPWeight::MIN;
+		let mut min = [TSPWeight::MAX; MAX_DIMENSIONS];
+		let mut max = [TSPWeight::MIN; MAX_DIMENSIONS];
 
 		for node in data
 		{
-			min_x = tsp_weight_min(node.tsp_node.x, min_x);
-			min_y = tsp_weight_min(node.tsp_node.y, min_y);
-
-			max_x = tsp_weight_max(node.tsp_node.x, max_x);
-			max_y = tsp_weight_max(node.tsp_node.y, max_y);
+			for dimension in 0..dimensions
+			{
+				min[dimension] = tsp_weight_min(node.tsp_node.coordinate(dimension), min[dimension]);
+				max[dimension] = tsp_weight_max(node.tsp_node.coordinate(dimension), max[dimension]);
+			}
 		}
 
-		if (max_x - min_x).abs() > (max_y - min_y).abs()
+		let mut best_dimension = 0;
+		for dimension in 1..dimensions
 		{
-			return E_SPLIT_AXIS::X;
-		}
-		else
-		{
-			return E_SPLIT_AXIS::Y;
+			if (max[dimension] - min[dimension]).abs() >= (max[best_dimension] - min[best_dimension]).abs()
+			{
+				best_dimension = dimension;
+			}
 		}
+
+		return E_SPLIT_AXIS::from_dimension(best_dimension);
 	}
 
 	/// Once the axis has been determined, this function computes the cut node
@@ -195,7 +253,7 @@ KDtree
 	fn
 	determine_cut_node
 	(
-		data:                          &Vec<KDtreeNodeData>,
+		data:                          &[KDtreeNodeData],
 		axis:                          E_SPLIT_AXIS,
 		random_generator:              &mut StdRng,
 	)
@@ -206,19 +264,11 @@ KDtree
 
 		// Draw random samples
 		let mut samples: Vec<KDtreeNodeData> = data
-			.choose_multiple(random_generator, number_of_random_samples)
-			.map(|node| node.clone())
+			.choose_multiple(random_generator, number_of_random_samples).copied()
 			.collect();
 
 		// Sort the randomly drawn samples so that the median can be selected
-		samples.sort_by(
-			|a, b|
-			match axis
-			{
-				E_SPLIT_AXIS::X => a.tsp_node.x.partial_cmp(&b.tsp_node.x).unwrap(),
-				E_SPLIT_AXIS::Y => a.tsp_node.y.partial_cmp(&b.tsp_node.y).unwrap(),
-			}
-		);
+		samples.sort_by(|a, b| a.axis_compare(b, axis));
 
 		// Approximate the median node
 		return samples[samples.len()/2];
@@ -255,6 +305,12 @@ KDtree
 	{
 		let mut nearests_nodes = Vec::new();
 
+		// Nothing to find in here
+		if self.enabled_count == 0
+		{
+			return nearests_nodes;
+		}
+
 		if let Some(unpacked_root) = self.root
 		{
 			// First of all, add the unpacked root if it is different from 
@@ -267,7 +323,7 @@ KDtree
 				&& bounds.contains(&unpacked_root.tsp_node)
 			{
 				nearests_nodes.push((
-					unpacked_root.tsp_node.clone(),
+					unpacked_root.tsp_node,
 					distance_to_root
 				));
 			}
@@ -289,7 +345,7 @@ KDtree
 			// Get the largest distance currently known in nearest nodes
 			// Also considering the current best known radius to use
 			nearests_nodes.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap());
-			let radius = if nearests_nodes.len() > 0
+			let radius = if !nearests_nodes.is_empty()
 			{
 				tsp_weight_min(
 					current_radius, 
@@ -302,31 +358,22 @@ KDtree
 			};
 
 			// Check with the bounds at this node
-			let needs_visit_on_other_side;
-			if comparison_with_root == Ordering::Less
+			let cut_dimension   = self.cut_axis.unwrap().dimension();
+
+			// Translate the radius into how far apart the coordinates may be, 
+			// as this depends on the distance metric in use
+			let coordinate_radius = tsp_data.max_coordinate_difference(radius, cut_dimension, tsp_node);
+			let node_coordinate = tsp_node.coordinate(cut_dimension);
+			let root_coordinate = unpacked_root.tsp_node.coordinate(cut_dimension);
+
+			let needs_visit_on_other_side = if comparison_with_root == Ordering::Less
 			{
-				match self.cut_axis.unwrap()
-				{
-					E_SPLIT_AXIS::X => {
-						needs_visit_on_other_side = tsp_node.x + radius >= unpacked_root.tsp_node.x || (bounds.are_bounded() && nearests_nodes.len() < count);
-					},
-					E_SPLIT_AXIS::Y => {
-						needs_visit_on_other_side = tsp_node.y + radius >= unpacked_root.tsp_node.y || (bounds.are_bounded() && nearests_nodes.len() < count);
-					},
-				}
+				node_coordinate + coordinate_radius >= root_coordinate || (bounds.are_bounded() && nearests_nodes.len() < count)
 			}
 			else
 			{
-				match self.cut_axis.unwrap()
-				{
-					E_SPLIT_AXIS::X => {
-						needs_visit_on_other_side = tsp_node.x - radius < unpacked_root.tsp_node.x || (bounds.are_bounded() && nearests_nodes.len() < count);
-					},
-					E_SPLIT_AXIS::Y => {
-						needs_visit_on_other_side = tsp_node.y - radius < unpacked_root.tsp_node.y || (bounds.are_bounded() && nearests_nodes.len() < count);
-					},
-				}
-			}
+				node_coordinate - coordinate_radius < root_coordinate || (bounds.are_bounded() && nearests_nodes.len() < count)
+			};
 
 			// If the bounds with the current radius are surpassed, we need to 
 			// visit the other child node as well.
@@ -361,7 +408,7 @@ KDtree
 
 				let distance = tsp_data.get_distance_between(tsp_node, &kd_node.tsp_node);
 				
-				nearests_nodes.push((kd_node.tsp_node.clone(), distance));
+				nearests_nodes.push((kd_node.tsp_node, distance));
 			}
 		}
 
@@ -369,8 +416,7 @@ KDtree
 
 		return nearests_nodes
 			.iter()
-			.take(count)
-			.map(|element| element.clone())
+			.take(count).copied()
 			.collect();
 	}
 
@@ -394,6 +440,8 @@ KDtree
 		self.change_node_enable(node, true);
 	}
 
+	/// Sets whether the given node is enabled and keeps the number of enabled
+	/// nodes of the subtrees up to date. Returns if the value has changed
 	fn
 	change_node_enable
 	(
@@ -401,40 +449,59 @@ KDtree
 		node:                          &TSPNode,
 		new_enable_value:              bool,
 	)
+	-> bool
 	{
-		if self.root.is_some()
+		let changed = if let Some(root) = self.root.as_mut()
 		{
-			if self.root.unwrap().tsp_node.id == node.id
-			{
-				self.root.as_mut().unwrap().enabled = new_enable_value;
-			}
-
-			// node is not the root, so let's see if we have to go left or right
-			let comparison_with_root = KDtreeNodeData::new(*node).axis_compare(&self.root.unwrap(), self.cut_axis.unwrap());
-
-			if comparison_with_root == Ordering::Less
+			if root.tsp_node.id == node.id
 			{
-				self.l_child.as_deref_mut().unwrap().change_node_enable(node, new_enable_value);
+				let changed = root.enabled != new_enable_value;
+				root.enabled = new_enable_value;
+				changed
 			}
 			else
 			{
-				self.r_child.as_deref_mut().unwrap().change_node_enable(node, new_enable_value);
+				// node is not the root, so let's see if we have to go left or right
+				let comparison_with_root = KDtreeNodeData::new(*node).axis_compare(root, self.cut_axis.unwrap());
+
+				if comparison_with_root == Ordering::Less
+				{
+					self.l_child.as_deref_mut().unwrap().change_node_enable(node, new_enable_value)
+				}
+				else
+				{
+					self.r_child.as_deref_mut().unwrap().change_node_enable(node, new_enable_value)
+				}
 			}
 		}
 		else
 		{
-			for mut kd_node in self.bucket.as_mut().unwrap().iter_mut()
+			let mut changed = false;
+			for kd_node in self.bucket.as_mut().unwrap().iter_mut()
 			{
 				if kd_node.tsp_node.id == node.id
 				{
+					changed = kd_node.enabled != new_enable_value;
 					kd_node.enabled = new_enable_value;
-					return;
+					break;
 				}
 			}
+			changed
+		};
+
+		if changed
+		{
+			if new_enable_value { self.enabled_count += 1; } else { self.enabled_count -= 1; }
 		}
+
+		return changed;
 	}
 
 
+	/// Gets for each quadrant (or octant in case of 3 dimensions) around the 
+	/// given tsp_node the count-many nearest nodes. These are combined with 
+	/// their distance to tsp_node and sorted according to these in ascending
+	/// order
 	pub fn
 	all_quadrant_nearest
 	(
@@ -446,32 +513,29 @@ KDtree
 	-> Vec<(TSPNode, TSPWeight)>
 	{
 		// Variable setup
-		let mut local_bounds;
+		let dimensions = tsp_data.distance.dimensions();
 		let mut nearests = Vec::new();
 
-		// Check the right upper corner
-		local_bounds = LocalBounds::new();
-		local_bounds.x_lower = tsp_node.x;
-		local_bounds.y_lower = tsp_node.y;
-		nearests.append(&mut self.internal_nearests(tsp_node, count, &local_bounds, TSPWeight::MAX, tsp_data));
-
-		// Check the right lower corner
-		local_bounds = LocalBounds::new();
-		local_bounds.x_lower = tsp_node.x;
-		local_bounds.y_upper = tsp_node.y;
-		nearests.append(&mut self.internal_nearests(tsp_node, count, &local_bounds, TSPWeight::MAX, tsp_data));
-
-		// Check the left lower corner
-		local_bounds = LocalBounds::new();
-		local_bounds.x_upper = tsp_node.x;
-		local_bounds.y_upper = tsp_node.y;
-		nearests.append(&mut self.internal_nearests(tsp_node, count, &local_bounds, TSPWeight::MAX, tsp_data));
-
-		// Check the left upper corner
-		local_bounds = LocalBounds::new();
-		local_bounds.x_upper = tsp_node.x;
-		local_bounds.y_lower = tsp_node.y;
-		nearests.append(&mut self.internal_nearests(tsp_node, count, &local_bounds, TSPWeight::MAX, tsp_data));
+		// Each bit of the orthant index decides if the given node is the lower
+		// or the upper bound along the corresponding dimension
+		for orthant in 0..(1 << dimensions)
+		{
+			let mut local_bounds = LocalBounds::new();
+
+			for dimension in 0..dimensions
+			{
+				if orthant & (1 << dimension) == 0
+				{
+					local_bounds.lower[dimension] = tsp_node.coordinate(dimension);
+				}
+				else
+				{
+					local_bounds.upper[dimension] = tsp_node.coordinate(dimension);
+				}
+			}
+
+			nearests.append(&mut self.internal_nearests(tsp_node, count, &local_bounds, TSPWeight::MAX, tsp_data));
+		}
 
 		// Sort the nearest nodes
 		nearests.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap());
@@ -484,13 +548,13 @@ KDtree
 }
 
 
+/// Bounds (along each dimension) for restricting a nearest neighbor search
+/// to a certain region, e.g. a quadrant
 struct
 LocalBounds
 {
-	pub x_lower:                             TSPWeight,
-	pub x_upper:                             TSPWeight,
-	pub y_lower:                             TSPWeight,
-	pub y_upper:                             TSPWeight
+	pub lower:                               [TSPWeight; MAX_DIMENSIONS],
+	pub upper:                               [TSPWeight; MAX_DIMENSIONS],
 }
 
 impl
@@ -503,10 +567,8 @@ LocalBounds
 	{
 		LocalBounds 
 		{ 
-			x_lower: TSPWeight::MIN, 
-			x_upper: TSPWeight::MAX, 
-			y_lower: TSPWeight::MIN, 
-			y_upper: TSPWeight::MAX 
+			lower: [TSPWeight::MIN; MAX_DIMENSIONS], 
+			upper: [TSPWeight::MAX; MAX_DIMENSIONS], 
 		}
 	}
 
@@ -518,10 +580,11 @@ LocalBounds
 	)
 	-> bool
 	{
-		   self.x_lower <= tsp_node.x
-		&& self.x_upper >= tsp_node.x
-		&& self.y_lower <= tsp_node.y
-		&& self.y_upper >= tsp_node.y
+		(0..MAX_DIMENSIONS).all(
+			|dimension| 
+			   self.lower[dimension] <= tsp_node.coordinate(dimension)
+			&& self.upper[dimension] >= tsp_node.coordinate(dimension)
+		)
 	}
 
 	pub fn
@@ -531,16 +594,17 @@ LocalBounds
 	)
 	-> bool
 	{
-		   self.x_lower > TSPWeight::MIN
-		|| self.x_upper < TSPWeight::MAX
-		|| self.y_lower > TSPWeight::MIN
-		|| self.y_upper < TSPWeight::MAX
+		(0..MAX_DIMENSIONS).any(
+			|dimension| 
+			   self.lower[dimension] > TSPWeight::MIN
+			|| self.upper[dimension] < TSPWeight::MAX
+		)
 	}
 
 }
 
 impl
-BBData
+BBData<'_>
 {
 	/// Constructs the KD tree for the given TSP data. If the KD tree already
 	/// exists, the function skips the creation and immediately returns
@@ -560,8 +624,74 @@ BBData
 		let time_measurement_start = Instant::now();
 
 		// Construct the KD tree
-		self.kd_tree = Some(KDtree::new(&self.tsp_data, &mut self.random_generator));
+		self.kd_tree = Some(KDtree::new(self.tsp_data, &mut self.random_generator));
 
-		println!("KD Tree Build Time : {}", (time_measurement_start.elapsed().as_micros() as f64) / 1000000.0);
+		if (self.config.verbose)
+		{
+			println!("KD Tree Build Time : {}", (time_measurement_start.elapsed().as_micros() as f64) / 1000000.0);
+		}
 	}
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests
+{
+	use rand::Rng;
+
+	use crate::tsp_lib::distance::EDistance;
+
+	use super::*;
+
+	/// Creates a random coordinate in the DDD.MM format used by GEO instances
+	fn
+	random_geographical_coordinate
+	(
+		random_generator:              &mut StdRng,
+		min:                           i32,
+		max:                           i32,
+	)
+	-> TSPWeight
+	{
+		let degrees = random_generator.gen_range(min..max) as TSPWeight;
+		let minutes = random_generator.gen_range(0..60) as TSPWeight / 100.0;
+		return if degrees < 0.0 { degrees - minutes } else { degrees + minutes };
+	}
+
+	#[test]
+	fn
+	geographical_nearests_match_brute_force
+	()
+	{
+		let mut random_generator: StdRng = rand_seeder::Seeder::from(42).make_rng();
+
+		// Spread over the whole world, close to a pole and along the date line
+		for (latitudes, longitudes) in [((-89, 89), (-179, 179)), ((80, 89), (-179, 179)), ((-10, 10), (170, 179))]
+		{
+			let mut tsp_data = TSPData::empty(500);
+			tsp_data.set_distance_metric(EDistance::GEOGRAPHICAL);
+			for node_id in 0..500
+			{
+				let latitude = random_geographical_coordinate(&mut random_generator, latitudes.0, latitudes.1);
+				let mut longitude = random_geographical_coordinate(&mut random_generator, longitudes.0, longitudes.1);
+				if longitudes.0 > 0 && random_generator.gen_bool(0.5)
+				{
+					longitude = -longitude;
+				}
+				tsp_data.add_node(&TSPNode::new(node_id, latitude, longitude));
+			}
+
+			let kd_tree = KDtree::new(&tsp_data, &mut random_generator);
+			for node in &tsp_data.nodes
+			{
+				let mut distances = tsp_data.nodes.iter()
+					.filter(|other_node| other_node.id != node.id)
+					.map(|other_node| tsp_data.get_distance_between(node, other_node))
+					.collect::<Vec<TSPWeight>>();
+				distances.sort_by(|a, b| a.partial_cmp(b).unwrap());
+
+				let nearests = kd_tree.nearests(node, 5, &tsp_data).iter().map(|&(_, distance)| distance).collect::<Vec<TSPWeight>>();
+				assert_eq!(nearests, distances[..5]);
+			}
+		}
+	}
+}
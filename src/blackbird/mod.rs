@@ -1,4 +1,7 @@
 pub mod data;
 pub mod kd;
+pub mod delaunay;
 pub mod initial_tour;
-pub mod heuristic;
\ No newline at end of file
+pub mod heuristic;
+pub mod solver;
+pub mod error;
@@ -0,0 +1,227 @@
+use std::time::Duration;
+
+use crate::blackbird::data::bb_data::BBData;
+use crate::blackbird::error::SolverError;
+use crate::blackbird::initial_tour::method::EInitialTourMethod;
+use crate::blackbird::data::flipper::EFlipperMethod;
+use crate::blackbird::heuristic::kick::EKickType;
+use crate::blackbird::heuristic::lk_params::LKParams;
+use crate::blackbird::heuristic::sparse_edge_map::ESparseEdgeMethod;
+use crate::blackbird::data::flipper::Flipper;
+use crate::blackbird::data::bb_flipper::BBFlipper;
+use crate::blackbird::data::bb_two_level_flipper::BBTwoLevelFlipper;
+use crate::blackbird::data::bb_array_flipper::BBArrayFlipper;
+use crate::blackbird::data::bb_splay_flipper::BBSplayFlipper;
+use crate::tsp_lib::data::TSPData;
+use crate::tsp_lib::node::*;
+
+/// The configuration of a Solver, i.e. all the settings that control how the
+/// heuristic gets applied to a TSP instance. The default values are the same
+/// as the ones of the command line interface. 
+#[derive(Clone, Debug)]
+pub struct
+SolverConfig
+{
+	/// How to create the initial tour
+	pub init_method:                   EInitialTourMethod,
+
+	/// The data structure for storing the tour during the heuristic
+	pub flipper_method:                EFlipperMethod,
+
+	/// How to choose the edges that get removed by a kick
+	pub kick_type:                     EKickType,
+
+	/// Verbose output to terminal, e.g. the runtimes of the individual steps
+	/// Without it, the solver does not print anything (apart from warnings)
+	pub verbose:                       bool,
+
+	/// A time limit for when to stop applying the heurisitic, measured from
+	/// the start of solving the instance
+	/// The insertion heuristics finish their tour quickly once it is reached,
+	/// the computation of the sparse edge set stops and CLK is skipped if it
+	/// is reached before CLK starts. The other initial tour methods and the
+	/// Delaunay triangulation can't be interrupted though and may exceed the
+	/// time limit on large instances
+	pub time_limit:                    Duration,
+
+	/// A tour length limit for when to stop applying the heuristic
+	pub length_limit:                  TSPWeight,
+
+	/// Seed for the random generator. If seed is 0, use current time as seed.
+	pub random_generator_seed:         u64,
+
+	/// The number of times to apply the CLK heuristic. These trials are
+	/// independent of each other and the best tour of all of them is kept
+	pub number_of_runs:                u64,
+
+	/// Merge the tours of all trials by applying CLK once more to the union
+	/// graph of these tours, keeping the edges common to all of them fixed
+	pub tour_merging:                  bool,
+
+	/// The number of threads running the trials of CLK in parallel. If this
+	/// is 0, use the available parallelism of the system.
+	pub number_of_threads:             usize,
+
+	/// The number of kicks performed by each run of CLK. If this is 0, use
+	/// the number of nodes.
+	pub number_of_kicks:               usize,
+
+	/// Stop a run of CLK after this many kicks without improvement
+	pub stall_count:                   usize,
+
+	/// How to compute the sparse set of good edges, using the union of the
+	/// edges of all given methods
+	pub sparse_edge_methods:           Vec<ESparseEdgeMethod>,
+
+	/// The number of nearest nodes for the nearest sparse edge method
+	pub nearest_count:                 usize,
+
+	/// The number of good edges per node when using alpha-nearness
+	pub alpha_nearness_count:          usize,
+
+	/// Use quadrant #-nearest for the sparse edge set
+	pub quadrant_nearest_count:        usize,
+
+	/// The parameters that control the search performed by LK
+	pub lk_params:                     LKParams,
+}
+
+impl
+Default
+for
+SolverConfig
+{
+	fn
+	default
+	()
+	-> Self
+	{
+		SolverConfig
+		{
+			init_method:               EInitialTourMethod::QuickBoruvka,
+			flipper_method:            EFlipperMethod::LinkedList,
+			kick_type:                 EKickType::WALK,
+			verbose:                   false,
+			time_limit:                Duration::from_secs(1000000),
+			length_limit:              0.0,
+			random_generator_seed:     0,
+			number_of_runs:            0,
+			tour_merging:              false,
+			number_of_threads:         0,
+			number_of_kicks:           0,
+			stall_count:               10000000,
+			sparse_edge_methods:       vec![ESparseEdgeMethod::QuadrantNearest],
+			nearest_count:             3,
+			alpha_nearness_count:      5,
+			quadrant_nearest_count:    2,
+			lk_params:                 LKParams::default(),
+		}
+	}
+}
+
+/// The result of solving a TSP instance
+#[derive(Clone, Debug)]
+pub struct
+SolverResult
+{
+	/// The best tour found, given as cycle of the internal node IDs (0 to n-1)
+	pub tour:                          Vec<TSPNodeID>,
+
+	/// The best tour found, given as cycle of the node IDs of the input file
+	pub external_tour:                 Vec<usize>,
+
+	/// The length of the initial tour
+	pub initial_tour_length:           TSPWeight,
+
+	/// The length of the best tour found
+	pub length:                        TSPWeight,
+
+	/// The total runtime of the solver
+	pub runtime:                       Duration,
+
+	/// The number of kicks performed by all runs of CLK
+	pub kick_count:                    usize,
+
+	/// The number of kicks after which LK found a new best tour
+	pub improving_kick_count:          usize,
+
+	/// The number of kicks of the tour merging that were dropped, as no kick
+	/// that keeps all fixed edges could be found
+	pub dropped_kick_count:            usize,
+
+	/// The length of the best tour found by each trial of CLK
+	pub trial_lengths:                 Vec<TSPWeight>,
+}
+
+/// The main entry point for using blackbird as a library. A Solver applies
+/// the CLK heuristic with its configuration to TSP instances that are either
+/// read from file or built in code. 
+pub struct
+Solver
+{
+	pub config:                        SolverConfig,
+}
+
+impl
+Solver
+{
+	/// Creates a new solver with the given configuration
+	pub fn
+	new
+	(
+		config:                        SolverConfig
+	)
+	-> Solver
+	{
+		Solver { config: config }
+	}
+
+	/// Solves the given TSP instance. Fails if the instance is too small, the
+	/// LK parameters are invalid or no valid initial tour can be created
+	pub fn
+	solve
+	(
+		&self,
+		tsp_data:                      &TSPData,
+	)
+	-> Result<SolverResult, SolverError>
+	{
+		match self.config.flipper_method
+		{
+			EFlipperMethod::LinkedList => self.solve_with_flipper::<BBFlipper>(tsp_data),
+			EFlipperMethod::TwoLevel   => self.solve_with_flipper::<BBTwoLevelFlipper>(tsp_data),
+			EFlipperMethod::Array      => self.solve_with_flipper::<BBArrayFlipper>(tsp_data),
+			EFlipperMethod::Splay      => self.solve_with_flipper::<BBSplayFlipper>(tsp_data),
+		}
+	}
+
+	/// Solves the given TSP instance using the given data structure for 
+	/// storing the tour, regardless of the flipper method of the configuration
+	pub fn
+	solve_with_flipper<F: Flipper>
+	(
+		&self,
+		tsp_data:                      &TSPData,
+	)
+	-> Result<SolverResult, SolverError>
+	{
+		self.config.lk_params.validate()?;
+
+		let mut bb_data = BBData::new(tsp_data, self.config.clone());
+		return bb_data.main_heuristic::<F>();
+	}
+
+	/// Solves the TSP instance given by 2-dimensional euclidean coordinates.
+	/// The internal node IDs of the resulting tour are the indices of the
+	/// coordinates.
+	pub fn
+	solve_coordinates
+	(
+		&self,
+		coordinates:                   &[(TSPWeight, TSPWeight)],
+	)
+	-> Result<SolverResult, SolverError>
+	{
+		return self.solve(&TSPData::from_coordinates(coordinates));
+	}
+}
@@ -1,6 +1,15 @@
+use std::str::FromStr;
+use std::time::Duration;
+
 use clap::Parser;
 
-use crate::tsp_lib::node::TSPWeight;
+use blackbird::EInitialTourMethod;
+use blackbird::EFlipperMethod;
+use blackbird::EKickType;
+use blackbird::ESparseEdgeMethod;
+use blackbird::LKParams;
+use blackbird::SolverConfig;
+use blackbird::tsp_lib::node::TSPWeight;
 
 #[derive(Clone, Parser, Debug)]
 #[command(version)]
@@ -13,17 +22,37 @@ CliArgs
 	#[arg(short='i', long, required=false, default_value="")]
 	pub node_input_file_path: String,
 
-	/// How to create the initial tour ('r': Random; 'b': Boruvka; 'q': Quick Boruvka [default])
-	#[arg(short='c', long, required=false, default_value="q")]
-	pub init_tour_method: char,
+	/// Do not round euclidean distances (EUC_2D, EUC_3D) to integers as prescribed by TSPLIB, so tour lengths no longer match the published optima
+	#[arg(long, required=false)]
+	pub no_tsplib_rounding: bool,
+
+	/// The path to the .tour output file for the final tour (not written if empty)
+	#[arg(short='o', long, required=false, default_value="")]
+	pub output_tour: String,
+
+	/// How to create the initial tour ('r': Random; 'b': Boruvka; 'q': Quick Boruvka [default]; 'g': Greedy; 'n': Nearest neighbor; 'h': Hilbert space-filling curve; 'c': Christofides; 'i': Nearest insertion; 'f': Farthest insertion; 'p': Cheapest insertion; 'a': Random insertion; the insertion methods only try the positions next to the nearest tour nodes)
+	#[arg(short='c', long, required=false, default_value="q", value_parser=parse_variant::<EInitialTourMethod>)]
+	pub init_tour_method: EInitialTourMethod,
+
+	/// The path to a .tour file to use as initial tour instead (not used if empty)
+	#[arg(short='f', long, required=false, default_value="")]
+	pub initial_tour_file: String,
+
+	/// How to store the tour during the heuristic ('l': Linked list [default]; 't': Two-level list; 'a': Array; 's': Splay tree)
+	#[arg(short='F', long, required=false, default_value="l", value_parser=parse_variant::<EFlipperMethod>)]
+	pub flipper_method: EFlipperMethod,
+
+	/// How to choose the edges removed by a kick ('w': Walk [default]; 'r': Random; 'g': Geometric; 'c': Close; 's': Segment reversal; 'i': Segment insertion; 'n': New random tour)
+	#[arg(short='k', long, required=false, default_value="w", value_parser=parse_variant::<EKickType>)]
+	pub kick_type: EKickType,
 
 	/// Verbose output to terminal
 	#[arg(short='v', long, required=false)]
 	pub verbose: bool,
 
-	/// A time limit for when to stop applying the heurisitic to guarantee a given runtime
-	#[arg(short='t', long, required=false, default_value="1000000")]
-	pub time_limit: u64,
+	/// A time limit (in seconds, may be fractional) for when to stop applying the heurisitic to guarantee a given runtime
+	#[arg(short='t', long, required=false, default_value="1000000", value_parser=parse_time_limit)]
+	pub time_limit: Duration,
 
 	/// A tour length limit for when to stop applying the heuristic once a certain length is reached
 	#[arg(short='l', long, required=false, default_value="0.0")]
@@ -33,11 +62,191 @@ CliArgs
 	#[arg(short='s', long, required=false, default_value="0")]
 	pub random_generator_seed: u64,
 
-	/// The number of times to apply the CLK heuristic
+	/// The number of independent trials of the CLK heuristic, keeping the best tour
 	#[arg(short='r', long, required=false, default_value="0")]
 	pub number_of_runs: u64,
 
+	/// Merge the tours of all trials, fixing the edges common to all of them
+	#[arg(short='m', long, required=false)]
+	pub tour_merging: bool,
+
+	/// The number of threads running the trials in parallel. If this is 0, use all available cores.
+	#[arg(short='j', long, required=false, default_value="0")]
+	pub number_of_threads: usize,
+
+	/// The number of kicks performed by each run of CLK. If this is 0, use the number of nodes.
+	#[arg(short='R', long, required=false, default_value="0")]
+	pub number_of_kicks: usize,
+
+	/// Stop a run of CLK after this many kicks without improvement
+	#[arg(short='S', long, required=false, default_value="10000000")]
+	pub stall_count: usize,
+
+	/// How to compute the sparse edge set, combining the given methods ('q': Quadrant nearest [default]; 'n': Nearest; 'd': Delaunay; 'a': Alpha-nearness), e.g. 'qn'
+	// The fully qualified Vec keeps clap from expecting multiple values
+	#[arg(short='e', long, required=false, default_value="q", value_parser=parse_sparse_edge_methods)]
+	pub sparse_edge_methods: ::std::vec::Vec<ESparseEdgeMethod>,
+
+	/// The number of nearest nodes for the nearest sparse edge method
+	#[arg(short='n', long, required=false, default_value="3")]
+	pub nearest_count: usize,
+
+	/// The number of good edges per node with the smallest alpha values
+	#[arg(long, required=false, default_value="5")]
+	pub alpha_nearness_count: usize,
+
 	/// Use quadrant #-nearest for the sparse edge set
 	#[arg(short='q', long, required=false, default_value="2")]
 	pub quadrant_nearest_count: usize,
+
+	/// The number of alternatives LK tries at each level that allows backtracking, comma-separated [default: 4,3,3,2]
+	#[arg(long, required=false, value_delimiter=',')]
+	pub breadth: Option<Vec<usize>>,
+
+	/// The number of levels of LK that allow backtracking [default: 4]
+	#[arg(long, required=false)]
+	pub backtrack: Option<usize>,
+
+	/// The maximum depth of the search of LK [default: 25]
+	#[arg(long, required=false)]
+	pub max_depth: Option<usize>,
+
+	/// How many tour neighbors of the nodes involved in a kick get processed by the next LK run [default: 10]
+	#[arg(long, required=false)]
+	pub mark_level: Option<usize>,
+
+	/// The number of steps of the random walks of the walk kick [default: 50]
+	#[arg(long, required=false)]
+	pub walk_steps: Option<usize>,
+
+	/// The number of alternatives LK tries at the three levels of the alternate first step, comma-separated [default: 4,3,3]
+	#[arg(long, required=false, value_parser=parse_alternate_breadth)]
+	pub alternate_breadth: Option<[usize; 3]>,
+}
+
+impl
+CliArgs
+{
+	/// Converts the CLI arguments to the configuration of the solver
+	pub fn
+	solver_config
+	(
+		&self
+	)
+	-> SolverConfig
+	{
+		SolverConfig
+		{
+			init_method:               if self.initial_tour_file.is_empty()
+			                           {
+			                               self.init_tour_method.clone()
+			                           }
+			                           else
+			                           {
+			                               EInitialTourMethod::FromFile(self.initial_tour_file.clone())
+			                           },
+			flipper_method:            self.flipper_method.clone(),
+			kick_type:                 self.kick_type.clone(),
+			verbose:                   self.verbose,
+			time_limit:                self.time_limit,
+			length_limit:              self.length_limit,
+			random_generator_seed:     self.random_generator_seed,
+			number_of_runs:            self.number_of_runs,
+			tour_merging:              self.tour_merging,
+			number_of_threads:         self.number_of_threads,
+			number_of_kicks:           self.number_of_kicks,
+			stall_count:               self.stall_count,
+			sparse_edge_methods:       self.sparse_edge_methods.clone(),
+			nearest_count:             self.nearest_count,
+			alpha_nearness_count:      self.alpha_nearness_count,
+			quadrant_nearest_count:    self.quadrant_nearest_count,
+			lk_params:                 self.lk_params(),
+		}
+	}
+
+	/// Converts the CLI arguments to the LK parameters, using the default
+	/// values of the parameters that are not given
+	fn
+	lk_params
+	(
+		&self
+	)
+	-> LKParams
+	{
+		let mut lk_params = LKParams::default();
+
+		if let Some(breadth) = &self.breadth       { lk_params.breadth    = breadth.clone(); }
+		if let Some(backtrack) = self.backtrack    { lk_params.backtrack  = backtrack; }
+		if let Some(max_depth) = self.max_depth    { lk_params.max_depth  = max_depth; }
+		if let Some(mark_level) = self.mark_level  { lk_params.mark_level = mark_level; }
+		if let Some(walk_steps) = self.walk_steps  { lk_params.walk_steps = walk_steps; }
+
+		if let Some(alternate_breadth) = self.alternate_breadth
+		{
+			lk_params.alternate_look_ahead_max_1 = alternate_breadth[0];
+			lk_params.alternate_look_ahead_max_2 = alternate_breadth[1];
+			lk_params.alternate_look_ahead_max_3 = alternate_breadth[2];
+		}
+
+		lk_params
+	}
+}
+
+/// Parses an option that selects a variant by a single character, using the
+/// FromStr implementation of the corresponding enum
+fn
+parse_variant<T: FromStr>
+(
+	value: &str
+)
+-> Result<T, String>
+{
+	if value.chars().count() != 1
+	{
+		return Err(format!("'{}' is not a single character", value));
+	}
+
+	T::from_str(value).map_err(|_| format!("'{}' is not one of the listed values", value))
+}
+
+/// Parses the sparse edge methods, given as one character per method
+fn
+parse_sparse_edge_methods
+(
+	value: &str
+)
+-> Result<Vec<ESparseEdgeMethod>, String>
+{
+	value.chars()
+		.map(|method| parse_variant::<ESparseEdgeMethod>(method.to_string().as_str()))
+		.collect()
+}
+
+/// Parses the time limit given in seconds, which needs to be a non-negative
+/// (and not too large) number
+fn
+parse_time_limit
+(
+	value: &str
+)
+-> Result<Duration, String>
+{
+	let seconds = value.trim().parse::<f64>().map_err(|error| error.to_string())?;
+	Duration::try_from_secs_f64(seconds).map_err(|error| error.to_string())
+}
+
+/// Parses the three comma-separated values of the alternate breadth
+fn
+parse_alternate_breadth
+(
+	value: &str
+)
+-> Result<[usize; 3], String>
+{
+	let values = value.split(',')
+		.map(|part| part.trim().parse::<usize>().map_err(|error| format!("'{}': {}", part, error)))
+		.collect::<Result<Vec<usize>, String>>()?;
+
+	values.try_into()
+		.map_err(|values: Vec<usize>| format!("needs exactly three values but {} were given", values.len()))
 }
@@ -0,0 +1,38 @@
+#![allow(unused_parens)]
+// The code base favors explicit returns, explicit field names and upper case
+// enum variants over the more compact forms suggested by clippy
+#![allow(clippy::needless_return)]
+#![allow(clippy::redundant_field_names)]
+#![allow(clippy::upper_case_acronyms)]
+
+//! Blackbird as a library: Solve TSP instances (read from TSPLIB files or 
+//! built in code) with the re-implementation of Concorde's CLK heuristic.
+//!
+//! ```no_run
+//! use blackbird::{Solver, SolverConfig};
+//!
+//! let solver = Solver::new(SolverConfig::default());
+//! let result = solver.solve_coordinates(&[(0.0, 0.0), (1.0, 0.0), (1.0, 1.0), (0.0, 1.0)]).unwrap();
+//! println!("{:?} has length {}", result.tour, result.length);
+//! ```
+
+pub mod tsp_lib;
+pub mod blackbird;
+pub mod util;
+
+pub use crate::blackbird::solver::Solver;
+pub use crate::blackbird::solver::SolverConfig;
+pub use crate::blackbird::solver::SolverResult;
+pub use crate::blackbird::error::SolverError;
+pub use crate::blackbird::initial_tour::method::EInitialTourMethod;
+pub use crate::blackbird::data::flipper::EFlipperMethod;
+pub use crate::blackbird::data::flipper::Flipper;
+pub use crate::blackbird::heuristic::kick::EKickType;
+pub use crate::blackbird::heuristic::sparse_edge_map::ESparseEdgeMethod;
+pub use crate::blackbird::heuristic::lk_params::LKParams;
+pub use crate::blackbird::data::bb_tour::BBTourError;
+pub use crate::tsp_lib::data::TSPData;
+pub use crate::tsp_lib::error::TSPLibError;
+pub use crate::tsp_lib::reader::read_tsplib_file;
+pub use crate::tsp_lib::reader::read_tsplib_tour_file;
+pub use crate::tsp_lib::writer::write_tsplib_tour_file;
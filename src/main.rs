@@ -1,14 +1,12 @@
-#![allow(unused_parens)]
-
 mod cli;
-mod tsp_lib;
-mod blackbird;
-mod util;
 
 use clap::Parser;
 
+use blackbird::Solver;
+use blackbird::read_tsplib_file;
+use blackbird::write_tsplib_tour_file;
+
 use cli::CliArgs;
-use crate::blackbird::data::bb_data::BBData;
 
 // Blackbird:
 // B Bringing to you a derivation of the
@@ -22,15 +20,16 @@ use crate::blackbird::data::bb_data::BBData;
 // D Duplicate.
 
 // DESIGN DECISIONS
-// - Focus on 2 dimensions, perhaps 3 dimensions if time allows it
+// - Focus on 2 dimensions, with 3 dimensions supported via the KD-tree using
+//   octants instead of quadrants
 //
 // - Main focus on euclidean distance, but trying to make extensions for other
 //   metrics possible
 //
 // - Using available kd implementation
 //
-// - No read in of initial cycle - Goal is to get a "good" solution without 
-//   prior knowledge
+// - Goal is to get a "good" solution without prior knowledge, but an initial
+//   cycle can be read in from a .tour file to continue from a previous tour
 //
 // - No read in of sparse edge set - instead this gets generated (kd-tree stuff)
 //
@@ -63,12 +62,48 @@ main()
 	// Print the seed used for this run for the shell script that calls Blackbird
 	println!("Seed : {}", args.random_generator_seed);
 
-	// Create the blackbird data structure
-	let mut bb_data = BBData::new(args);
+	// Read in the TSP instance
+	let mut tsp_data = match read_tsplib_file(&args.node_input_file_path)
+	{
+		Ok(tsp_data) => tsp_data,
+		Err(error)   => {
+			eprintln!("{}", error);
+			std::process::exit(1);
+		}
+	};
+
+	tsp_data.set_euclidean_rounding(!args.no_tsplib_rounding);
 
 	// Output the number of nodes for the shell script that calls Blackbird
-	println!("Number of nodes : {}", bb_data.tsp_data.n);
+	println!("Number of nodes : {}", tsp_data.n);
 
 	// Apply the CLK heuristic
-	bb_data.main_heuristic();
+	let result = match Solver::new(args.solver_config()).solve(&tsp_data)
+	{
+		Ok(result) => result,
+		Err(error) => {
+			eprintln!("{}", error);
+			std::process::exit(1);
+		}
+	};
+
+	// Output the results for the shell script that calls Blackbird
+	println!("Initial tour length: {}", result.initial_tour_length);
+	for (index, trial_length) in result.trial_lengths.iter().enumerate()
+	{
+		println!("Trial {} tour length : {}", index, trial_length);
+	}
+	println!("Improving kicks : {} / {}", result.improving_kick_count, result.kick_count);
+	println!("Final tour length : {}", result.length);
+	println!("Total runtime : {}", result.runtime.as_secs_f64());
+
+	// Write the final tour to file (if requested)
+	if !args.output_tour.is_empty()
+	{
+		if let Err(error) = write_tsplib_tour_file(&args.output_tour, &tsp_data, &result.tour)
+		{
+			eprintln!("{}", error);
+			std::process::exit(1);
+		}
+	}
 }
@@ -1,15 +1,25 @@
+use std::collections::HashMap;
+
 use crate::tsp_lib::node::*;
 use crate::tsp_lib::distance::*;
 
 /// This struct stores the general information of a TSPLIB problem instance
 /// This is only the data as provided by the input file and does not feature
 /// attributes that are generated by the algorithm itself
+/// Nodes are numbered internally from 0 to n-1, while the IDs they have in the
+/// input file are kept for reporting them (e.g. when writing a tour).
 pub struct
 TSPData
 {
+	pub name: String,
+	pub comment: String,
 	pub n: usize,
 	pub nodes: Vec<TSPNode>,
 	pub distance: EDistance,
+	pub weight_matrix: Option<Vec<TSPWeight>>,
+	pub round_euclidean: bool,
+	pub external_ids: Vec<usize>,
+	pub internal_ids: HashMap<usize, TSPNodeID>,
 }
 
 impl
@@ -26,23 +36,119 @@ TSPData
 	{
 		TSPData 
 		{ 
+			name: String::new(),
+			comment: String::new(),
 			n: 0, 
 			nodes: Vec::with_capacity(capacity), 
-			distance: EDistance::EUCLIDEAN_2D 
+			distance: EDistance::EUCLIDEAN_2D,
+			weight_matrix: None,
+			round_euclidean: true,
+			external_ids: Vec::with_capacity(capacity),
+			internal_ids: HashMap::with_capacity(capacity),
 		}
 	}
 
+	/// Creates a new TSP instance using the 2-dimensional euclidean distance 
+	/// from the given coordinates. The IDs of the nodes are the indices of 
+	/// their coordinates. 
+	/// As this is not a TSPLIB instance, the distances do not get rounded
+	pub fn
+	from_coordinates
+	(
+		coordinates: &[(TSPWeight, TSPWeight)]
+	)
+	-> TSPData
+	{
+		let mut tsp_data = TSPData::empty(coordinates.len());
+		tsp_data.set_euclidean_rounding(false);
+
+		for (node_id, (x, y)) in coordinates.iter().enumerate()
+		{
+			tsp_data.add_node(&TSPNode::new(node_id, *x, *y));
+		}
+
+		return tsp_data;
+	}
+
 	/// Adds a new node to the TSP instance
+	/// Its external ID is the one TSPLIB would use, i.e. counting from 1
 	pub fn
 	add_node
 	(
 		&mut self,
 		new_node: &TSPNode
 	)
+	{
+		self.add_node_with_external_id(new_node, new_node.id + 1);
+	}
+
+	/// Adds a new node to the TSP instance, remembering the ID the node has
+	/// in the input file so that the node can be reported with it later on
+	pub fn
+	add_node_with_external_id
+	(
+		&mut self,
+		new_node: &TSPNode,
+		external_id: usize
+	)
 	{
 		self.n += 1;
 		self.nodes.push(new_node.to_owned());
 		self.nodes.sort_by(|a, b| a.id.partial_cmp(&b.id).unwrap());
+		self.external_ids.push(external_id);
+		self.internal_ids.insert(external_id, new_node.id);
+	}
+
+	/// Gets the ID of a node as given in the input file via its internal ID
+	pub fn
+	external_id
+	(
+		&self,
+		node_id: TSPNodeID
+	)
+	-> usize
+	{
+		return self.external_ids[node_id];
+	}
+
+	/// Gets the internal ID of a node via the ID it has in the input file
+	/// Returns None if there is no node with that ID
+	pub fn
+	internal_id
+	(
+		&self,
+		external_id: usize
+	)
+	-> Option<TSPNodeID>
+	{
+		return self.internal_ids.get(&external_id).copied();
+	}
+
+	/// Setter Method for the name of this TSP instance
+	pub fn
+	set_name
+	(
+		&mut self,
+		name: &str
+	)
+	{
+		self.name = name.to_string();
+	}
+
+	/// Adds a line to the comment of this TSP instance, as TSPLIB files may 
+	/// contain more than one COMMENT line
+	pub fn
+	add_comment
+	(
+		&mut self,
+		comment: &str
+	)
+	{
+		if !self.comment.is_empty()
+		{
+			self.comment.push('\n');
+		}
+		self.comment.push_str(comment);
 	}
 
 	/// Get a (read-only) reference to a TSP Node via its ID
@@ -70,10 +176,34 @@ TSPData
 		self.distance = distance;
 	}
 
+	/// Setter Method for rounding the euclidean distances (EUC_2D and EUC_3D)
+	/// to the nearest integer, just like TSPLIB prescribes it. This is the
+	/// default, as only then the tour lengths can be compared to the published
+	/// optimal tour lengths
+	pub fn
+	set_euclidean_rounding
+	(
+		&mut self,
+		round_euclidean: bool
+	)
+	{
+		self.round_euclidean = round_euclidean;
+	}
+
+	/// Setter Method for the explicitly given edge weights of this TSP instance
+	/// These are stored as a full n x n matrix in row-major order
+	pub fn
+	set_weight_matrix
+	(
+		&mut self,
+		weight_matrix: Vec<TSPWeight>
+	)
+	{
+		assert_eq!(weight_matrix.len(), self.n * self.n);
+		self.weight_matrix = Some(weight_matrix);
+	}
+
 	/// Gets the distance between two nodes of the TSP instance via their IDs
-	/// Note that this delibaretly gives a warning to emphasize that this 
-	/// requires attention in case additional distance measures are introduced
-	/// in the future
 	pub fn
 	get_distance_between_via_id
 	(
@@ -84,13 +214,10 @@ TSPData
 	-> TSPWeight
 	{
 		// Speed up the special case of getting the distance of a node to itself
+		// This is zero for all of the supported metrics
 		if node_1_id == node_2_id
 		{
-			match self.distance
-			{
-				EDistance::EUCLIDEAN_2D => { return 0.0; },
-				_ => todo!(),
-			}
+			return 0.0;
 		}
 
 		// Get the two nodes we want to know the distance between of
@@ -101,9 +228,8 @@ TSPData
 	}
 
 	/// Gets the distance between two nodes of the TSP instance
-	/// Note that this delibaretly gives a warning to emphasize that this 
-	/// requires attention in case additional distance measures are introduced
-	/// in the future
+	/// The actual computation depends on the metric, see EDistance::compute,
+	/// unless the weights are given explicitly
 	pub fn
 	get_distance_between
 	(
@@ -113,18 +239,43 @@ TSPData
 	)
 	-> TSPWeight
 	{
+		// Explicitly given weights only need to be looked up
+		if let Some(weight_matrix) = &self.weight_matrix
+		{
+			return weight_matrix[node_1.id * self.n + node_2.id];
+		}
+
 		// Depending on the metric to be used, calculate the distance
-		return match self.distance
+		let distance = self.distance.compute(node_1, node_2);
+
+		if self.round_euclidean && (self.distance == EDistance::EUCLIDEAN_2D || self.distance == EDistance::EUCLIDEAN_3D)
+		{
+			return nint(distance);
+		}
+
+		return distance;
+	}
+
+	/// Gives an upper bound on how far two nodes can be apart along the given
+	/// coordinate axis if their distance is at most the given one, see
+	/// EDistance::max_coordinate_difference. Rounding the euclidean distances
+	/// may shorten them by up to 0.5, which needs to be taken into account
+	pub fn
+	max_coordinate_difference
+	(
+		&self,
+		distance: TSPWeight,
+		dimension: usize,
+		node: &TSPNode
+	)
+	-> TSPWeight
+	{
+		if self.round_euclidean
 		{
-			EDistance::EUCLIDEAN_2D => {
-				(
-					(node_1.x - node_2.x).powi(2) + (node_1.y - node_2.y).powi(2)
-				).sqrt()
-			},
-			_ => {
-				panic!("TSPData get_distance_between: Distance not implemented!")
-			},
+			return self.distance.max_coordinate_difference(distance + 0.5, dimension, node);
 		}
+
+		return self.distance.max_coordinate_difference(distance, dimension, node);
 	}
 
 	/// Count how many nodes the TSP instance contains 
@@ -147,8 +298,10 @@ TSPData
 	)
 	{
 		println!("TSP DATA:");
+		println!("Name: {}", self.name);
+		println!("Comment: {}", self.comment);
 		println!("Distance: {:?}", self.distance);
 		println!("Nodes:");
-		self.nodes.iter().for_each(|node| node.print());
+		self.nodes.iter().for_each(|node| println!("{:>10}: {:>10} {:>10} {:>10}", self.external_id(node.id), node.x, node.y, node.z));
 	}
 }
\ No newline at end of file
@@ -1,17 +1,28 @@
 use std::str::FromStr;
 
+use crate::tsp_lib::node::*;
+
 /// The different types of distances supported
-/// Currently this only allows for 2-dimensional euclidean distances as this
-/// one of the more common cases with TSP instances. 
-/// Anything 3-dimenional would require adding a third dimension everywhere
-/// where the existing 2 dimensions are needed for making decisions, e.g. 
-/// building a KD-tree.
+/// These follow the definitions given in the TSPLIB95 documentation, including
+/// the prescribed rounding to integer values. The only exception are the
+/// euclidean distances, which are computed without rounding here. TSPData
+/// rounds them for instances read from TSPLIB files, unless this is turned
+/// off via TSPData::set_euclidean_rounding.
 #[allow(non_camel_case_types)]
-#[derive(Debug)]
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub enum
 EDistance
 {
 	EUCLIDEAN_2D,
+	CEIL_2D,
+	MANHATTAN_2D,
+	MAXIMUM_2D,
+	EUCLIDEAN_3D,
+	MANHATTAN_3D,
+	MAXIMUM_3D,
+	GEOGRAPHICAL,
+	PSEUDO_EUCLIDEAN,
+	EXPLICIT,
 }
 
 impl
@@ -33,7 +44,249 @@ EDistance
 		match s.to_uppercase().as_str()
 		{
 			"EUC_2D" 	=> Ok(EDistance::EUCLIDEAN_2D),
+			"CEIL_2D" 	=> Ok(EDistance::CEIL_2D),
+			"MAN_2D" 	=> Ok(EDistance::MANHATTAN_2D),
+			"MAX_2D" 	=> Ok(EDistance::MAXIMUM_2D),
+			"EUC_3D" 	=> Ok(EDistance::EUCLIDEAN_3D),
+			"MAN_3D" 	=> Ok(EDistance::MANHATTAN_3D),
+			"MAX_3D" 	=> Ok(EDistance::MAXIMUM_3D),
+			"GEO" 		=> Ok(EDistance::GEOGRAPHICAL),
+			"ATT" 		=> Ok(EDistance::PSEUDO_EUCLIDEAN),
+			"EXPLICIT" 	=> Ok(EDistance::EXPLICIT),
 			_ 			=> Err(())
 		}
 	}
-}
\ No newline at end of file
+}
+
+/// The radius of the earth (in km) as defined by TSPLIB for GEO instances
+const GEO_EARTH_RADIUS: TSPWeight = 6378.388;
+
+/// The (slightly inaccurate) value of pi as used by TSPLIB for GEO instances
+#[allow(clippy::approx_constant)]
+const GEO_PI: TSPWeight = 3.141592;
+
+/// Rounds to the nearest integer the way TSPLIB does it, i.e. via the C
+/// expression (int) (x + 0.5)
+pub fn
+nint
+(
+	x: TSPWeight
+)
+-> TSPWeight
+{
+	(x + 0.5).floor()
+}
+
+/// Converts a coordinate of a GEO instance, given in the DDD.MM format (degrees
+/// and minutes), to its latitude or longitude in radians
+pub fn
+geographical_radians
+(
+	coordinate: TSPWeight
+)
+-> TSPWeight
+{
+	let degrees = coordinate.trunc();
+	let minutes = coordinate - degrees;
+	GEO_PI * (degrees + 5.0 * minutes / 3.0) / 180.0
+}
+
+/// Gives an upper bound on how far the coordinates of two nodes of a GEO
+/// instance can be apart along the given axis if their distance is at most
+/// the given one, with the first node being the given one
+/// Their central angle is less than (distance + 1) / GEO_EARTH_RADIUS, which
+/// bounds the difference of the latitudes directly. The difference of the
+/// longitudes follows from the haversine formula, but only as long as the
+/// nodes are not close to a pole or on different sides of the date line
+fn
+geographical_max_coordinate_difference
+(
+	distance:  TSPWeight,
+	dimension: usize,
+	node:      &TSPNode
+)
+-> TSPWeight
+{
+	let angle = (distance + 1.0) / GEO_EARTH_RADIUS;
+	if angle >= GEO_PI / 2.0
+	{
+		return TSPWeight::MAX;
+	}
+
+	let radians = if dimension == 0
+	{
+		angle
+	}
+	else
+	{
+		let latitude = geographical_radians(node.x).abs();
+		if latitude + angle >= GEO_PI / 2.0
+		{
+			return TSPWeight::MAX;
+		}
+
+		let haversine = (angle / 2.0).sin().powi(2) / (latitude.cos() * (latitude + angle).cos());
+		if haversine >= 1.0
+		{
+			return TSPWeight::MAX;
+		}
+		2.0 * haversine.sqrt().asin()
+	};
+
+	// A coordinate in the DDD.MM format differs from its value in degrees by
+	// less than 2/3, so the difference of two coordinates by less than 4/3
+	let degrees = radians * 180.0 / GEO_PI;
+	if dimension == 1 && node.y.abs() + degrees + 4.0 / 3.0 >= 180.0
+	{
+		return TSPWeight::MAX;
+	}
+
+	degrees + 4.0 / 3.0
+}
+
+impl
+EDistance
+{
+	/// Computes the distance between two nodes according to this metric
+	/// Explicitly given weights can't be computed from the nodes alone but
+	/// need to be looked up via the weight matrix stored in TSPData
+	pub fn
+	compute
+	(
+		&self,
+		node_1: &TSPNode,
+		node_2: &TSPNode
+	)
+	-> TSPWeight
+	{
+		let dx = node_1.x - node_2.x;
+		let dy = node_1.y - node_2.y;
+		let dz = node_1.z - node_2.z;
+
+		match self
+		{
+			EDistance::EUCLIDEAN_2D => {
+				(dx.powi(2) + dy.powi(2)).sqrt()
+			},
+			EDistance::CEIL_2D => {
+				(dx.powi(2) + dy.powi(2)).sqrt().ceil()
+			},
+			EDistance::MANHATTAN_2D => {
+				nint(dx.abs() + dy.abs())
+			},
+			EDistance::MAXIMUM_2D => {
+				nint(dx.abs()).max(nint(dy.abs()))
+			},
+			EDistance::EUCLIDEAN_3D => {
+				(dx.powi(2) + dy.powi(2) + dz.powi(2)).sqrt()
+			},
+			EDistance::MANHATTAN_3D => {
+				nint(dx.abs() + dy.abs() + dz.abs())
+			},
+			EDistance::MAXIMUM_3D => {
+				nint(dx.abs()).max(nint(dy.abs())).max(nint(dz.abs()))
+			},
+			EDistance::GEOGRAPHICAL => {
+				// Note that TSPLIB swaps the meaning of x and y for GEO, i.e.
+				// x is the latitude and y the longitude
+				let latitude_1  = geographical_radians(node_1.x);
+				let longitude_1 = geographical_radians(node_1.y);
+				let latitude_2  = geographical_radians(node_2.x);
+				let longitude_2 = geographical_radians(node_2.y);
+
+				let q1 = (longitude_1 - longitude_2).cos();
+				let q2 = (latitude_1  - latitude_2 ).cos();
+				let q3 = (latitude_1  + latitude_2 ).cos();
+
+				(
+					GEO_EARTH_RADIUS 
+					* (0.5 * ((1.0 + q1) * q2 - (1.0 - q1) * q3)).acos() 
+					+ 1.0
+				).trunc()
+			},
+			EDistance::PSEUDO_EUCLIDEAN => {
+				let r = ((dx.powi(2) + dy.powi(2)) / 10.0).sqrt();
+				let t = nint(r);
+				if t < r { t + 1.0 } else { t }
+			},
+			EDistance::EXPLICIT => {
+				panic!("EDistance compute: Explicit weights must be looked up via TSPData!")
+			},
+		}
+	}
+
+	/// Gives an upper bound on how far two nodes can be apart along the given
+	/// coordinate axis if their distance is at most the given one, with the 
+	/// first node being the given one. This is required by the KD-tree for 
+	/// deciding if the other side of a cut needs to be visited during a 
+	/// nearest neighbor search. 
+	/// For GEO, the bound depends on the position of the node, see 
+	/// geographical_max_coordinate_difference
+	pub fn
+	max_coordinate_difference
+	(
+		&self,
+		distance:  TSPWeight,
+		dimension: usize,
+		node:      &TSPNode
+	)
+	-> TSPWeight
+	{
+		match self
+		{
+			EDistance::EUCLIDEAN_2D     => distance,
+			EDistance::CEIL_2D          => distance,
+			EDistance::MANHATTAN_2D     => distance + 0.5,
+			EDistance::MAXIMUM_2D       => distance + 0.5,
+			EDistance::EUCLIDEAN_3D     => distance,
+			EDistance::MANHATTAN_3D     => distance + 0.5,
+			EDistance::MAXIMUM_3D       => distance + 0.5,
+			EDistance::GEOGRAPHICAL     => geographical_max_coordinate_difference(distance, dimension, node),
+			EDistance::PSEUDO_EUCLIDEAN => distance * (10.0 as TSPWeight).sqrt(),
+			EDistance::EXPLICIT         => TSPWeight::MAX,
+		}
+	}
+
+	/// The number of coordinates each node has for this metric
+	pub fn
+	dimensions
+	(
+		&self
+	)
+	-> usize
+	{
+		match self
+		{
+			EDistance::EUCLIDEAN_3D     => 3,
+			EDistance::MANHATTAN_3D     => 3,
+			EDistance::MAXIMUM_3D       => 3,
+			_                           => 2,
+		}
+	}
+
+	/// Checks if the distances grow with the euclidean distances between the
+	/// 2-dimensional coordinates of the nodes (apart from rounding), in which
+	/// case the Delaunay triangulation contains a minimum spanning tree
+	pub fn
+	is_euclidean_2d
+	(
+		&self
+	)
+	-> bool
+	{
+		matches!(self, EDistance::EUCLIDEAN_2D | EDistance::CEIL_2D | EDistance::PSEUDO_EUCLIDEAN)
+	}
+
+	/// Checks if the distances are derived from the coordinates of the nodes
+	/// If not, the geometric parts of the heuristic (e.g. the KD-tree and the
+	/// quadrant neighbors) need to fall back to non-geometric alternatives
+	pub fn
+	is_geometric
+	(
+		&self
+	)
+	-> bool
+	{
+		*self != EDistance::EXPLICIT
+	}
+}
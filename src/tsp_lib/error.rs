@@ -0,0 +1,64 @@
+use std::fmt;
+
+/// The errors that can occur while reading (or writing) TSPLIB files
+/// Line numbers start at 1, just like in any text editor
+#[derive(Debug)]
+pub enum
+TSPLibError
+{
+	Io(std::io::Error),
+	UnknownKeyword { line: usize, keyword: String },
+	UnsupportedFeature { line: usize, feature: String },
+	BadNumber { line: usize, value: String },
+	DimensionMismatch { expected: usize, found: usize },
+	DuplicateNode { line: usize, node: usize },
+}
+
+impl
+fmt::Display
+for
+TSPLibError
+{
+	fn
+	fmt
+	(
+		&self,
+		f: &mut fmt::Formatter<'_>
+	)
+	-> fmt::Result
+	{
+		match self
+		{
+			TSPLibError::Io(error) 
+				=> write!(f, "TSPLIB: I/O error: {}", error),
+			TSPLibError::UnknownKeyword { line, keyword } 
+				=> write!(f, "TSPLIB: Unknown keyword '{}' in line {}", keyword, line),
+			TSPLibError::UnsupportedFeature { line, feature } 
+				=> write!(f, "TSPLIB: Unsupported feature '{}' in line {}", feature, line),
+			TSPLibError::BadNumber { line, value } 
+				=> write!(f, "TSPLIB: Could not parse number '{}' in line {}", value, line),
+			TSPLibError::DimensionMismatch { expected, found } 
+				=> write!(f, "TSPLIB: Expected {} entries due to the dimension but found {}", expected, found),
+			TSPLibError::DuplicateNode { line, node } 
+				=> write!(f, "TSPLIB: Node {} in line {} has already been given before", node, line),
+		}
+	}
+}
+
+impl std::error::Error for TSPLibError {}
+
+impl
+From<std::io::Error>
+for
+TSPLibError
+{
+	fn
+	from
+	(
+		error: std::io::Error
+	)
+	-> Self
+	{
+		TSPLibError::Io(error)
+	}
+}
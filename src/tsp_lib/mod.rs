@@ -1,4 +1,7 @@
 pub mod node;
 pub mod distance;
+pub mod weight_format;
 pub mod data;
-pub mod reader;
\ No newline at end of file
+pub mod error;
+pub mod reader;
+pub mod writer;
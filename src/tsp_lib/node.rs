@@ -2,8 +2,12 @@
 pub type TSPNodeID = usize;
 pub type TSPWeight = f64;
 
+/// The maximum number of dimensions of the coordinates of a node
+pub const MAX_DIMENSIONS: usize = 3;
+
 /// A simple data structure for representing one of the many nodes of a TSP 
-/// problem instance with its identifier and (2D) coordinates
+/// problem instance with its identifier and (2D or 3D) coordinates
+/// For 2-dimensional instances, the z coordinate is always zero
 #[derive(Copy, Clone, Debug)]
 pub struct
 TSPNode
@@ -11,6 +15,7 @@ TSPNode
 	pub id: TSPNodeID,
 	pub x:  TSPWeight,
 	pub y:  TSPWeight,
+	pub z:  TSPWeight,
 }
 
 impl
@@ -25,7 +30,39 @@ TSPNode
 	)
 	-> TSPNode
 	{
-		TSPNode {id: id, x: x, y: y}
+		TSPNode {id: id, x: x, y: y, z: 0.0}
+	}
+
+	pub fn
+	new_3d
+	(
+		id: TSPNodeID,
+		x:  TSPWeight,
+		y:  TSPWeight,
+		z:  TSPWeight
+	)
+	-> TSPNode
+	{
+		TSPNode {id: id, x: x, y: y, z: z}
+	}
+
+	/// Gets the coordinate of the node along the given dimension, with 0, 1 
+	/// and 2 corresponding to x, y and z
+	pub fn
+	coordinate
+	(
+		&self,
+		dimension: usize
+	)
+	-> TSPWeight
+	{
+		match dimension
+		{
+			0 => self.x,
+			1 => self.y,
+			2 => self.z,
+			_ => panic!("TSPNode coordinate: Invalid dimension {}!", dimension),
+		}
 	}
 
 	pub fn
@@ -34,6 +71,6 @@ TSPNode
 		&self
 	)
 	{
-		println!("{:>10}: {:>10} {:>10}", self.id, self.x, self.y);
+		println!("{:>10}: {:>10} {:>10} {:>10}", self.id, self.x, self.y, self.z);
 	}
-}
\ No newline at end of file
+}
@@ -1,30 +1,53 @@
 use std::fs::OpenOptions;
-use std::path::Path;
 use std::io::BufReader;
 use std::io::BufRead;
 use std::str::FromStr;
 
 use crate::tsp_lib::node::*;
 use crate::tsp_lib::distance::*;
+use crate::tsp_lib::weight_format::*;
 use crate::tsp_lib::data::*;
+use crate::tsp_lib::error::*;
 
-fn 
-is_node_coord_line
+/// The data sections of a TSPLIB file that consist of lines of numbers
+/// Needed by the reader to determine what such a line represents
+#[allow(non_camel_case_types)]
+#[derive(PartialEq)]
+enum
+ESection
+{
+	NONE,
+	NODE_COORD,
+	DISPLAY_DATA,
+	EDGE_WEIGHT,
+}
+
+/// Parses a single number of a TSPLIB file, e.g. a coordinate or a weight
+/// A missing number is reported the same way as one that can't be parsed
+fn
+parse_number<T: FromStr>
 (
-	line: &str
-) 
--> bool 
+	value:   Option<&str>,
+	line_nr: usize,
+)
+-> Result<T, TSPLibError>
 {
-	for character in line.chars() 
-	{
-		if 
-			!matches!(character, '0'..='9' | '.' | 'e' | 'E' | '-' | '+' ) && 
-			!character.is_whitespace() 
-		{
-			return false;
-		}
-	}
-	return true;
+	let value = value.unwrap_or_default();
+	value.parse::<T>().map_err(|_| TSPLibError::BadNumber { line: line_nr, value: value.to_string() })
+}
+
+/// Gets the value of a line of the form "KEYWORD : value" with the value
+/// being kept as is, e.g. for names and comments that may contain colons
+fn
+keyword_value<'a>
+(
+	line:    &'a str,
+	keyword: &str,
+)
+-> &'a str
+{
+	let value = line.trim().strip_prefix(keyword).unwrap_or_default().trim_start();
+	return value.strip_prefix(':').unwrap_or(value).trim();
 }
 
 /// Reads in a file in TSPLIB format 
@@ -32,36 +55,35 @@ is_node_coord_line
 /// http://comopt.ifi.uni-heidelberg.de/software/TSPLIB95/tsp95.pdf
 /// Please note that not all of the features that are defined for TSPLIB are
 /// supported by this reader. 
-/// For example, distances other than 2-dimensional euclidean or any
-/// 3-dimensional data is not suppored at the current stage due to the already
-/// complex nature of this project.
+/// For example, only symmetric instances are suppored at the current stage due
+/// to the already complex nature of this project. Supported edge weight types
+/// are EUC_2D, CEIL_2D, MAN_2D, MAX_2D, GEO, ATT, EUC_3D, MAN_3D and MAX_3D, as
+/// well as EXPLICIT weights given in one of the (symmetric) matrix formats.
 /// The result of this is a TSPData struct that contains the necessary
 /// information for constructing a TSP instance, like node data. 
+/// Any problem with the file, be it malformed or using unsupported features,
+/// is reported via a TSPLibError instead.
 pub fn
 read_tsplib_file
 (
 	path_string: &String
 )
--> TSPData
+-> Result<TSPData, TSPLibError>
 {
 
-	// Parameter checking
-	if path_string == ""
-	{
-		panic!("Empty input file path String!");
-	}
+	// Holds the value of key "DIMENSION" (if given)
+	let mut dimension = 0usize;
 
-	// Get the path to the specified file
-	let path = Path::new(path_string);
+	// Holds the value of key "EDGE_WEIGHT_FORMAT" (if given) and the weights
+	// from the EDGE_WEIGHT_SECTION in the order they appear in the file
+	let mut weight_format = EEdgeWeightFormat::FUNCTION;
+	let mut edge_weights = Vec::new();
 
-	// Check that the specified file actually exists
-	if !path.exists()
-	{
-		panic!("Non-existant input file!");
-	}
+	// The section the current line belongs to
+	let mut section = ESection::NONE;
 
-	// Holds the value of key "DIMENSION" (if given)
-	let mut dimension = 0usize;
+	// The line in which the EDGE_WEIGHT_TYPE is given, for error reporting
+	let mut edge_weight_type_line_nr = 0;
 
 	// The TSPData that will eventually get returned
 	let mut tsp_data = TSPData::empty(dimension);
@@ -70,26 +92,22 @@ read_tsplib_file
 	let file = OpenOptions::new()
 		.write(false)
 		.read(true)
-		.open(path)
-		.expect("Could not open .tsp file");
+		.open(path_string)?;
 	let reader = BufReader::new(file);
 
 	// Go through every line
-	for (line_nr, optional_line) in reader.lines().enumerate()
+	for (line_index, optional_line) in reader.lines().enumerate()
 	{
-		// If the
-		if optional_line.is_err()
-		{
-			panic!("TSPLIB Reader: Could not read line {}", line_nr);
-		}
+		// Line numbers as humans count them
+		let line_nr = line_index + 1;
 
 		// Unwrap the line, replace colons and trim whitespace
-		let untrimmed_line = optional_line.unwrap();
+		let untrimmed_line = optional_line?;
 		let replaced_colons = untrimmed_line.replace(":", " ");
 		let line = replaced_colons.trim();
 
 		// Skip empty lines
-		if line.len() == 0
+		if line.is_empty()
 		{
 			continue;
 		}
@@ -99,66 +117,107 @@ read_tsplib_file
 
 		// Next, go through the different keys that might be contained
 
-		// Ignore the name of the TSP instance
-		if parts.next().unwrap_or_default().starts_with("NAME")
-		{}
+		// Get the name of the TSP instance
+		let keyword = parts.next().unwrap_or_default();
+		if keyword.starts_with("NAME")
+		{
+			tsp_data.set_name(keyword_value(&untrimmed_line, "NAME"));
+		}
 
 		else if line.starts_with("TYPE") 
 		{
-			if !parts.next().unwrap_or_default().eq("TSP")
+			let problem_type = parts.next().unwrap_or_default();
+			if !problem_type.eq("TSP")
 			{
-				panic!("TSPLIB Reader: Not a TSP File!");
+				return Err(TSPLibError::UnsupportedFeature { line: line_nr, feature: format!("TYPE {}", problem_type) });
 			}
 		}
 
-		// Ignore any comments as they are not required for the actual TSP
+		// Keep any comments, even though they are not required for the TSP
 		else if line.starts_with("COMMENT")
-		{}
+		{
+			tsp_data.add_comment(keyword_value(&untrimmed_line, "COMMENT"));
+		}
 
 		// Get the number of dimensions 
 		else if line.starts_with("DIMENSION")
 		{
-			dimension = parts.next().unwrap_or("0").parse::<usize>().unwrap_or(0);
+			dimension = parse_number(parts.next(), line_nr)?;
 		}
 
 		// Get the distance measure to use for solving the TSP
 		else if line.starts_with("EDGE_WEIGHT_TYPE")
 		{
-			if let Ok(distance) = EDistance::from_str(parts.next().unwrap_or_default())
+			let value = parts.next().unwrap_or_default();
+			edge_weight_type_line_nr = line_nr;
+			if let Ok(distance) = EDistance::from_str(value)
 			{
 				tsp_data.set_distance_metric(distance);
 			}
 			else
 			{
-				panic!("TSPLIB Reader: Illegal EDGE_WEIGHT_TYPE value!");
+				return Err(TSPLibError::UnsupportedFeature { line: line_nr, feature: format!("EDGE_WEIGHT_TYPE {}", value) });
 			}
 		}
 
-		// Check if unsupported weight format
+		// Get the format in which explicit edge weights are given
 		else if line.starts_with("EDGE_WEIGHT_FORMAT")
 		{
-			panic!("TSPLIB Reader: EDGE_WEIGHT_FORMAT currently not supported!");
+			let value = parts.next().unwrap_or_default();
+			if let Ok(format) = EEdgeWeightFormat::from_str(value)
+			{
+				weight_format = format;
+			}
+			else
+			{
+				return Err(TSPLibError::UnsupportedFeature { line: line_nr, feature: format!("EDGE_WEIGHT_FORMAT {}", value) });
+			}
 		}
+
+		// Ignore how the nodes are supposed to be displayed
+		else if line.starts_with("DISPLAY_DATA_TYPE")
+		{}
 		
 		// Start of section that contains actual node data
 		else if line.starts_with("NODE_COORD_SECTION")
 		{
 			if tsp_data.node_count() > 0
 			{
-				panic!("TSPLIB Reader: A second NODE_COORD_SECTION!");
+				return Err(TSPLibError::UnsupportedFeature { line: line_nr, feature: "multiple node sections".to_string() });
 			}
+			section = ESection::NODE_COORD;
+		}
+
+		// Start of section that contains node coordinates only used for 
+		// displaying the nodes. For instances with explicit weights, these 
+		// are used as node coordinates, as they don't affect the distances
+		else if line.starts_with("DISPLAY_DATA_SECTION")
+		{
+			if tsp_data.node_count() > 0
+			{
+				return Err(TSPLibError::UnsupportedFeature { line: line_nr, feature: "multiple node sections".to_string() });
+			}
+			section = ESection::DISPLAY_DATA;
 		}
 		
-		// Explicit edge weights are also not supported
+		// Start of section that contains the explicit edge weights
 		else if line.starts_with("EDGE_WEIGHT_SECTION")
 		{
-			panic!("TSPLIB Reader: EDGE_WEIGHT_SECTION currently not supported!");
+			if !edge_weights.is_empty()
+			{
+				return Err(TSPLibError::UnsupportedFeature { line: line_nr, feature: "multiple EDGE_WEIGHT_SECTIONs".to_string() });
+			}
+			if tsp_data.distance != EDistance::EXPLICIT
+			{
+				return Err(TSPLibError::UnsupportedFeature { line: line_nr, feature: "EDGE_WEIGHT_SECTION for non-EXPLICIT EDGE_WEIGHT_TYPE".to_string() });
+			}
+			section = ESection::EDGE_WEIGHT;
 		}
 		
-		// Fixed edges section: Also not supported
+		// Fixed edges section: Not supported
 		else if line.starts_with("FIXED_EDGES_SECTION")
 		{
-			panic!("TSPLIB Reader: FIXED_EDGES_SECTION not supported!");
+			return Err(TSPLibError::UnsupportedFeature { line: line_nr, feature: "FIXED_EDGES_SECTION".to_string() });
 		}
 
 		// The end of the input file
@@ -167,42 +226,236 @@ read_tsplib_file
 			break;
 		}
 		
+		// Try to parse the line as edge weights
+		else if section == ESection::EDGE_WEIGHT
+		{
+			for weight in line.split_whitespace()
+			{
+				edge_weights.push(parse_number(Some(weight), line_nr)?);
+			}
+		}
+		
 		// Try to parse the line as node data
-		else if is_node_coord_line(line)
+		else if section != ESection::NONE
 		{
 			// Construct node struct
 			let mut node_parts = line.split_whitespace();
 
 			// Don't use numbering from the TSP but own "internal" numbering
-			let unused_node_id = node_parts.next().to_owned().unwrap_or("0").parse::<usize>().unwrap_or(0);
+			// The ID from the file is kept for reporting the node
+			let external_node_id: usize = parse_number(node_parts.next(), line_nr)?;
 
-			let node = TSPNode::new(
-				tsp_data.node_count(),
-				node_parts.next().to_owned().unwrap_or("0.0").parse::<f64>().unwrap_or(0.0),
-				node_parts.next().to_owned().unwrap_or("0.0").parse::<f64>().unwrap_or(0.0),
-			);
+			let x = parse_number(node_parts.next(), line_nr)?;
+			let y = parse_number(node_parts.next(), line_nr)?;
 
-			if unused_node_id == 0
+			// The third coordinate is only given for 3-dimensional metrics
+			let node = if tsp_data.distance.dimensions() == 3
 			{
-				panic!("TSPLIB Reader: Could not read node!");
+				let z = parse_number(node_parts.next(), line_nr)?;
+				TSPNode::new_3d(tsp_data.node_count(), x, y, z)
+			}
+			else
+			{
+				TSPNode::new(tsp_data.node_count(), x, y)
+			};
+
+			if external_node_id == 0
+			{
+				return Err(TSPLibError::BadNumber { line: line_nr, value: external_node_id.to_string() });
+			}
+
+			if tsp_data.internal_id(external_node_id).is_some()
+			{
+				return Err(TSPLibError::DuplicateNode { line: line_nr, node: external_node_id });
 			}
 			
-			tsp_data.add_node(&node);
+			tsp_data.add_node_with_external_id(&node, external_node_id);
 		}
 		
 		// In any other case: I have no idea what this line says
 		else
 		{
-			panic!("TSPLIB Reader: Unknown line type: {}", line);
+			return Err(TSPLibError::UnknownKeyword { line: line_nr, keyword: keyword.to_string() });
+		}
+	}
+
+	// Explicit weights need to be put into the weight matrix 
+	if tsp_data.distance == EDistance::EXPLICIT
+	{
+		// Without any display data, the nodes are not known yet and are
+		// created without (meaningful) coordinates
+		if tsp_data.node_count() == 0
+		{
+			for node_id in 0..dimension
+			{
+				tsp_data.add_node(&TSPNode::new(node_id, 0.0, 0.0));
+			}
+		}
+
+		let matrix_entries = weight_format.matrix_entries(dimension);
+
+		if weight_format == EEdgeWeightFormat::FUNCTION
+		{
+			return Err(TSPLibError::UnsupportedFeature { line: edge_weight_type_line_nr, feature: "EXPLICIT weights without EDGE_WEIGHT_FORMAT".to_string() });
+		}
+
+		if matrix_entries.len() != edge_weights.len()
+		{
+			return Err(TSPLibError::DimensionMismatch { expected: matrix_entries.len(), found: edge_weights.len() });
+		}
+
+		// As only symmetric instances are supported, the triangular formats 
+		// get mirrored along the diagonal
+		let mut weight_matrix = vec![0.0; dimension * dimension];
+		for ((row, column), weight) in matrix_entries.into_iter().zip(edge_weights)
+		{
+			weight_matrix[row * dimension + column] = weight;
+			if weight_format != EEdgeWeightFormat::FULL_MATRIX
+			{
+				weight_matrix[column * dimension + row] = weight;
+			}
+		}
+
+		if tsp_data.node_count() == dimension
+		{
+			tsp_data.set_weight_matrix(weight_matrix);
 		}
 	}
 
 	// Check that added nodes corresponds with the given dimension count
 	if tsp_data.node_count() != dimension
 	{
-		panic!("TSPLIB Reader: Dimension and node count do not match");
+		return Err(TSPLibError::DimensionMismatch { expected: dimension, found: tsp_data.node_count() });
 	}
 
 	// tsp_data.print();
-	return tsp_data;
-}
\ No newline at end of file
+	return Ok(tsp_data);
+}
+
+/// Reads in a tour of the given TSP instance from a file in TSPLIB TOUR format
+/// The node IDs in the file are the ones of the instance's input file and get
+/// mapped to the internal node IDs. Only the first tour of the TOUR_SECTION is
+/// read. Whether the result actually is a valid tour gets checked by the
+/// caller, this function only makes sure that it refers to existing nodes. 
+pub fn
+read_tsplib_tour_file
+(
+	path_string: &String,
+	tsp_data:    &TSPData,
+)
+-> Result<Vec<TSPNodeID>, TSPLibError>
+{
+	// Holds the value of key "DIMENSION" (if given)
+	let mut dimension = tsp_data.n;
+
+	// Whether the current line belongs to the TOUR_SECTION and whether the 
+	// end of the tour (given by -1) has been reached
+	let mut in_tour_section = false;
+	let mut tour_finished = false;
+
+	// The tour that will eventually get returned
+	let mut tour = Vec::with_capacity(tsp_data.n);
+
+	// The input .tour file and its reader
+	let file = OpenOptions::new()
+		.write(false)
+		.read(true)
+		.open(path_string)?;
+	let reader = BufReader::new(file);
+
+	// Go through every line
+	for (line_index, optional_line) in reader.lines().enumerate()
+	{
+		// Line numbers as humans count them
+		let line_nr = line_index + 1;
+
+		// Unwrap the line, replace colons and trim whitespace
+		let untrimmed_line = optional_line?;
+		let replaced_colons = untrimmed_line.replace(":", " ");
+		let line = replaced_colons.trim();
+
+		// Skip empty lines
+		if line.is_empty()
+		{
+			continue;
+		}
+
+		let mut parts = line.split_whitespace();
+		let keyword = parts.next().unwrap_or_default();
+
+		// Ignore the name and comments of the tour
+		if keyword.starts_with("NAME") || keyword.starts_with("COMMENT")
+		{}
+
+		else if keyword.starts_with("TYPE")
+		{
+			let file_type = parts.next().unwrap_or_default();
+			if !file_type.eq("TOUR")
+			{
+				return Err(TSPLibError::UnsupportedFeature { line: line_nr, feature: format!("TYPE {}", file_type) });
+			}
+		}
+
+		// The number of nodes of the tour needs to match the instance
+		else if keyword.starts_with("DIMENSION")
+		{
+			dimension = parse_number(parts.next(), line_nr)?;
+			if dimension != tsp_data.n
+			{
+				return Err(TSPLibError::DimensionMismatch { expected: tsp_data.n, found: dimension });
+			}
+		}
+
+		else if keyword.starts_with("TOUR_SECTION")
+		{
+			in_tour_section = true;
+		}
+
+		// The end of the input file
+		else if keyword.starts_with("EOF")
+		{
+			break;
+		}
+
+		// Try to parse the line as node IDs of the tour
+		else if in_tour_section
+		{
+			for value in line.split_whitespace()
+			{
+				let external_node_id: i64 = parse_number(Some(value), line_nr)?;
+
+				// The end of the tour
+				if external_node_id == -1
+				{
+					tour_finished = true;
+					break;
+				}
+
+				match tsp_data.internal_id(external_node_id as usize)
+				{
+					Some(node_id) if external_node_id > 0 => tour.push(node_id),
+					_ => return Err(TSPLibError::BadNumber { line: line_nr, value: value.to_string() }),
+				}
+			}
+
+			if tour_finished
+			{
+				break;
+			}
+		}
+
+		// In any other case: I have no idea what this line says
+		else
+		{
+			return Err(TSPLibError::UnknownKeyword { line: line_nr, keyword: keyword.to_string() });
+		}
+	}
+
+	// Check that the tour contains as many nodes as the instance
+	if tour.len() != dimension
+	{
+		return Err(TSPLibError::DimensionMismatch { expected: dimension, found: tour.len() });
+	}
+
+	return Ok(tour);
+}
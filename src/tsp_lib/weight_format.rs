@@ -0,0 +1,90 @@
+use std::str::FromStr;
+
+/// The different formats in which explicit edge weights can be given in the 
+/// EDGE_WEIGHT_SECTION of a TSPLIB file. As only symmetric instances are 
+/// supported, the column-wise formats are equivalent to their row-wise 
+/// counterparts with lower and upper swapped, e.g. reading the upper triangle
+/// column by column yields the same order as reading the lower one row by row.
+#[allow(non_camel_case_types)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum
+EEdgeWeightFormat
+{
+	FUNCTION,
+	FULL_MATRIX,
+	UPPER_ROW,
+	LOWER_ROW,
+	UPPER_DIAG_ROW,
+	LOWER_DIAG_ROW,
+}
+
+impl
+FromStr
+for
+EEdgeWeightFormat
+{
+	type Err = ();
+
+	/// Allows the creation of weight format enum values from given strings as
+	/// provided by TSPLIB input files.
+	fn 
+	from_str
+	(
+		s: &str
+	) 
+	-> Result<Self, Self::Err>
+	{
+		match s.to_uppercase().as_str()
+		{
+			"FUNCTION"          => Ok(EEdgeWeightFormat::FUNCTION),
+			"FULL_MATRIX"       => Ok(EEdgeWeightFormat::FULL_MATRIX),
+			"UPPER_ROW"         => Ok(EEdgeWeightFormat::UPPER_ROW),
+			"LOWER_ROW"         => Ok(EEdgeWeightFormat::LOWER_ROW),
+			"UPPER_DIAG_ROW"    => Ok(EEdgeWeightFormat::UPPER_DIAG_ROW),
+			"LOWER_DIAG_ROW"    => Ok(EEdgeWeightFormat::LOWER_DIAG_ROW),
+			"UPPER_COL"         => Ok(EEdgeWeightFormat::LOWER_ROW),
+			"LOWER_COL"         => Ok(EEdgeWeightFormat::UPPER_ROW),
+			"UPPER_DIAG_COL"    => Ok(EEdgeWeightFormat::LOWER_DIAG_ROW),
+			"LOWER_DIAG_COL"    => Ok(EEdgeWeightFormat::UPPER_DIAG_ROW),
+			_                   => Err(())
+		}
+	}
+}
+
+impl
+EEdgeWeightFormat
+{
+	/// Gives the matrix entries (as row and column index) in the order in 
+	/// which their weights are listed in the EDGE_WEIGHT_SECTION of an
+	/// instance with n nodes
+	pub fn
+	matrix_entries
+	(
+		&self,
+		n:                             usize,
+	)
+	-> Vec<(usize, usize)>
+	{
+		let mut entries = Vec::new();
+
+		for row in 0..n
+		{
+			let columns = match self
+			{
+				EEdgeWeightFormat::FUNCTION       => 0..0,
+				EEdgeWeightFormat::FULL_MATRIX    => 0..n,
+				EEdgeWeightFormat::UPPER_ROW      => (row+1)..n,
+				EEdgeWeightFormat::LOWER_ROW      => 0..row,
+				EEdgeWeightFormat::UPPER_DIAG_ROW => row..n,
+				EEdgeWeightFormat::LOWER_DIAG_ROW => 0..(row+1),
+			};
+
+			for column in columns
+			{
+				entries.push((row, column));
+			}
+		}
+
+		return entries;
+	}
+}
@@ -0,0 +1,113 @@
+use std::fs::File;
+use std::io::BufWriter;
+use std::io::Write;
+use std::path::Path;
+
+use crate::tsp_lib::node::*;
+use crate::tsp_lib::data::*;
+use crate::tsp_lib::error::*;
+
+/// Writes a tour of the given TSP instance to a file in TSPLIB TOUR format
+/// The tour is given as cycle of the internal node IDs, which get mapped back
+/// to the node IDs of the input file, so that the tour file can be used
+/// together with the original .tsp file.
+/// The name of the tour is derived from the name of the instance or, if the
+/// instance has no name, from the name of the output file.
+pub fn
+write_tsplib_tour_file
+(
+	path_string:                       &String,
+	tsp_data:                          &TSPData,
+	tour:                              &[TSPNodeID],
+)
+-> Result<(), TSPLibError>
+{
+	let name = if tsp_data.name.is_empty()
+	{
+		Path::new(path_string)
+			.file_stem()
+			.map(|stem| stem.to_string_lossy().to_string())
+			.unwrap_or_default()
+	}
+	else
+	{
+		format!("{}.tour", tsp_data.name)
+	};
+
+	let file = File::create(path_string)?;
+	let mut writer = BufWriter::new(file);
+
+	writeln!(writer, "NAME : {}", name)?;
+	writeln!(writer, "TYPE : TOUR")?;
+	writeln!(writer, "DIMENSION : {}", tour.len())?;
+	writeln!(writer, "TOUR_SECTION")?;
+
+	for node_id in tour
+	{
+		writeln!(writer, "{}", tsp_data.external_id(*node_id))?;
+	}
+
+	writeln!(writer, "-1")?;
+	writeln!(writer, "EOF")?;
+
+	writer.flush()?;
+	return Ok(());
+}
+
+#[cfg(test)]
+mod tests
+{
+	use std::fs;
+
+	use crate::tsp_lib::reader::*;
+
+	use super::*;
+
+	/// Writes the given content to a file in the temporary directory and
+	/// returns its path
+	fn
+	temporary_file
+	(
+		file_name:                     &str,
+		content:                       &str,
+	)
+	-> String
+	{
+		let path = std::env::temp_dir().join(format!("blackbird_{}_{}", std::process::id(), file_name));
+		fs::write(&path, content).unwrap();
+		return path.to_string_lossy().to_string();
+	}
+
+	#[test]
+	fn
+	tour_file_round_trip
+	()
+	{
+		// The node IDs of the file are not the internal ones
+		let tsp_path = temporary_file("round_trip.tsp", "NAME : round_trip\nTYPE : TSP\nDIMENSION : 5\nEDGE_WEIGHT_TYPE : EUC_2D\nNODE_COORD_SECTION\n10 0 0\n20 3 0\n30 3 4\n40 0 4\n50 1 2\nEOF\n");
+		let tsp_data = read_tsplib_file(&tsp_path).unwrap();
+
+		let tour = vec![3, 1, 4, 0, 2];
+		let tour_path = temporary_file("round_trip.tour", "");
+		write_tsplib_tour_file(&tour_path, &tsp_data, &tour).unwrap();
+
+		let content = fs::read_to_string(&tour_path).unwrap();
+		assert!(content.contains("TOUR_SECTION\n40\n20\n50\n10\n30\n-1\n"));
+		assert_eq!(read_tsplib_tour_file(&tour_path, &tsp_data).unwrap(), tour);
+
+		// A tour file of another instance does not match the dimension
+		let other_tour_path = temporary_file("round_trip_other.tour", "TYPE : TOUR\nDIMENSION : 4\nTOUR_SECTION\n10\n20\n30\n40\n-1\nEOF\n");
+		assert!(matches!(read_tsplib_tour_file(&other_tour_path, &tsp_data), Err(TSPLibError::DimensionMismatch { expected: 5, found: 4 })));
+
+		// Node IDs that are not part of the instance
+		let unknown_tour_path = temporary_file("round_trip_unknown.tour", "TYPE : TOUR\nTOUR_SECTION\n10 20 30 40 5\n-1\n");
+		assert!(matches!(read_tsplib_tour_file(&unknown_tour_path, &tsp_data), Err(TSPLibError::BadNumber { line: 3, .. })));
+
+		assert!(matches!(read_tsplib_tour_file(&String::from("/nonexistent/blackbird.tour"), &tsp_data), Err(TSPLibError::Io(_))));
+
+		for path in [tsp_path, tour_path, other_tour_path, unknown_tour_path]
+		{
+			fs::remove_file(path).unwrap();
+		}
+	}
+}
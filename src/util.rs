@@ -6,7 +6,7 @@ macro_rules! warn {
 	($warning_message:expr) 
 	=>
 	{
-		println!("WARNING: {}", $warning_message)
+		eprintln!("WARNING: {}", $warning_message)
 	}
 }
 